@@ -0,0 +1,34 @@
+//! Compares `Iterator::count()` on a pass-through `ParamFromFnIter` with 
+//! `ParamFromFnIter::count_via_data()`. Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use iter_map::IntoIterMap;
+
+const LEN: usize = 10_000_000;
+
+fn time<F>(name: &str, mut f: F)
+//
+where F: FnMut() -> usize,
+{
+    const RUNS: u32 = 10;
+
+    let mut total = Duration::ZERO;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        assert_eq!(black_box(f()), LEN);
+        total += start.elapsed();
+    }
+    println!("{}: {:?} per run", name, total / RUNS);
+}
+
+fn main()
+{
+    time("count", || {
+        (0..LEN).iter_map(|iter| black_box(iter).next()).count()
+    });
+    time("count_via_data", || {
+        (0..LEN).iter_map(|iter| black_box(iter).next()).count_via_data()
+    });
+}
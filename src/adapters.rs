@@ -0,0 +1,4707 @@
+
+//! Adapters built on `ParamFromFnIter`. Each adapter keeps its state, 
+//! including any closures passed to it, in the iterator's data and returns an
+//! `Adapter`, so the crate's accessors like `.data()` and `.into_data()` 
+//! remain available on the result.
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::VecDeque, rc::Rc, vec, vec::Vec};
+#[cfg(feature = "std")]
+use std::{collections::{HashMap, HashSet}, hash::Hash};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "alloc")]
+use core::cell::RefCell;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::iter::{FromIterator, Fuse, Peekable};
+use core::ops::{Range, Sub};
+
+use crate::{Adapter, ParamFromFnIter};
+
+/// The state kept by `IterMapExt::intersperse()` and 
+/// `IterMapExt::intersperse_with()` to decide whether an item or a separator
+/// comes next.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntersperseState
+{
+    /// The next output is an item.
+    Item,
+    /// The next output is a separator, if another item follows.
+    Separator,
+}
+
+/// The state kept by `IterMapExt::prepend()` and `IterMapExt::append()` to
+/// track whether the extra item has been yielded yet.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExtraState<T>
+{
+    /// The extra item hasn't been yielded.
+    Pending(T),
+    /// The extra item has been yielded.
+    Yielded,
+}
+
+impl<T> ExtraState<T>
+{
+    fn take(&mut self) -> Option<T>
+    {
+        match core::mem::replace(self, ExtraState::Yielded) {
+            ExtraState::Pending(item) => Some(item),
+            ExtraState::Yielded       => None,
+        }
+    }
+}
+
+/// The position of an item yielded by `IterMapExt::with_position()`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Position
+{
+    /// The first of several items.
+    First,
+    /// Neither the first nor the last item.
+    Middle,
+    /// The last of several items.
+    Last,
+    /// The only item.
+    Only,
+}
+
+/// An item yielded by `IterMapExt::zip_longest()`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pair<A, B>
+{
+    /// Both iterators produced an item.
+    Both(A, B),
+    /// Only the left iterator, `self`, produced an item.
+    Left(A),
+    /// Only the right iterator, `other`, produced an item.
+    Right(B),
+}
+
+/// The data of `IterMapExt::scan_map()`, `IterMapExt::filter_with_state()`
+/// and `IterMapExt::map_while_state()`: the source, the state threaded 
+/// through the closure, and the closure itself.
+///
+#[derive(Clone, Debug)]
+pub struct ScanState<I, S, G>
+{
+    /// The source iterator.
+    pub iter: I,
+    /// The state passed to the closure with each item.
+    pub state: S,
+    f: G,
+}
+
+/// The data of `IterMapExt::extract_map()`: the source, the closure, and 
+/// the number of items pulled from the source so far.
+///
+#[derive(Clone, Debug)]
+pub struct ExtractState<I, G>
+{
+    /// The source iterator.
+    pub iter: I,
+    f: G,
+    /// The number of items pulled from the source.
+    pub attempts: usize,
+}
+
+/// The data of `IterMapExt::array_chunks()`: the source and the items of 
+/// the chunk gathered so far.
+///
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct ArrayChunksState<I, T>
+{
+    /// The source iterator.
+    pub iter: I,
+    buffer: Vec<T>,
+}
+
+/// The iterator over the left halves returned by `IterMapExt::unzip_map()`.
+/// It shares the source and a buffer for each side with the iterator over 
+/// the right halves through a `SharedSide`.
+///
+#[cfg(feature = "alloc")]
+pub type UnzipLefts<I, A, B> = Adapter<SharedSide<UnzipShared<I, A, B>>, A>;
+
+/// The iterator over the right halves returned by `IterMapExt::unzip_map()`.
+///
+#[cfg(feature = "alloc")]
+pub type UnzipRights<I, A, B> = Adapter<SharedSide<UnzipShared<I, A, B>>, B>;
+
+/// The source and per-side buffers shared by `UnzipLefts` and `UnzipRights`.
+///
+#[cfg(feature = "alloc")]
+type UnzipShared<I, A, B> = (I, VecDeque<A>, VecDeque<B>);
+
+/// One side's handle on the state shared by a pair of iterators, such as 
+/// the two returned by `IterMapExt::tee()`. The state is kept in an 
+/// `Rc<RefCell<_>>` next to a flag for each side telling whether it's still
+/// alive. The handle isn't `Clone`, so there's exactly one per side, and 
+/// dropping it clears its flag so the other side stops buffering for it.
+///
+#[cfg(feature = "alloc")]
+pub struct SharedSide<S>
+{
+    shared: Rc<RefCell<(S, [bool; 2])>>,
+    side: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<S> SharedSide<S>
+{
+    /// Returns the handles of both sides on `state`.
+    ///
+    fn pair(state: S) -> (Self, Self)
+    {
+        let shared = Rc::new(RefCell::new((state, [true; 2])));
+        (SharedSide { shared: shared.clone(), side: 0 }, 
+         SharedSide { shared, side: 1 })
+    }
+}
+
+/// Implements Drop for SharedSide.
+///
+#[cfg(feature = "alloc")]
+impl<S> Drop for SharedSide<S>
+{
+    fn drop(&mut self)
+    {
+        self.shared.borrow_mut().1[self.side] = false;
+    }
+}
+
+/// One of the two iterators returned by `IterMapExt::tee()`. The source and 
+/// a buffer for each side are shared through a `SharedSide`, whose side 
+/// selects this iterator's buffer.
+///
+#[cfg(feature = "alloc")]
+pub type Tee<I> = Adapter<SharedSide<TeeShared<I>>, <I as Iterator>::Item>;
+
+/// The source and per-side buffers shared by the two `Tee` iterators.
+///
+#[cfg(feature = "alloc")]
+type TeeShared<I> = (I, [VecDeque<<I as Iterator>::Item>; 2]);
+
+/// One of the two iterators returned by `IterMapExt::split_at_predicate()`.
+/// The source, the predicate, the items of the leading run pulled ahead of
+/// time by the second half, and whether the split has been found are shared
+/// in an `Rc<RefCell<_>>`.
+///
+#[cfg(feature = "alloc")]
+pub type SplitHalf<I, G> = Adapter<Rc<RefCell<SplitShared<I, G>>>, 
+                                   <I as Iterator>::Item>;
+
+/// The state shared by the two `SplitHalf` iterators.
+///
+#[cfg(feature = "alloc")]
+type SplitShared<I, G> = (Peekable<I>, 
+                          G, 
+                          VecDeque<<I as Iterator>::Item>, 
+                          bool);
+
+/// A guard kept in the data of `IterMapExt::on_drop()` that invokes its 
+/// closure when dropped.
+///
+pub struct OnDrop<G>
+//
+where G: FnOnce(),
+{
+    f: Option<G>,
+}
+
+/// Implements Drop for OnDrop.
+///
+impl<G> Drop for OnDrop<G>
+//
+where G: FnOnce(),
+{
+    fn drop(&mut self)
+    {
+        if let Some(f) = self.f.take() {
+            f();
+        }
+    }
+}
+
+/// The integer types summed by `IterMapExt::running_sum_saturating()` and 
+/// `IterMapExt::running_sum_wrapping()`. It's implemented for all of the 
+/// primitive integers by forwarding to their inherent methods of the same 
+/// names.
+///
+pub trait OverflowAdd: Copy + Default
+{
+    /// Adds `rhs`, clamping at the type's bounds.
+    ///
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    /// Adds `rhs`, wrapping around at the type's bounds.
+    ///
+    fn wrapping_add(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_overflow_add {
+    ($($t:ty),*) => {
+        $(
+            impl OverflowAdd for $t
+            {
+                fn saturating_add(self, rhs: Self) -> Self
+                {
+                    <$t>::saturating_add(self, rhs)
+                }
+
+                fn wrapping_add(self, rhs: Self) -> Self
+                {
+                    <$t>::wrapping_add(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_overflow_add!(i8, i16, i32, i64, i128, isize, 
+                   u8, u16, u32, u64, u128, usize);
+
+/// A trait adding the crate's adapters to every `Iterator`.
+///
+pub trait IterMapExt: Iterator + Sized
+{
+    /// Returns an iterator over chunks of up to `size` items, each collected
+    /// into an owned `Vec`. The final chunk may be shorter than `size`.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = (1..=5).chunks(2).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![vec![1, 2], vec![3, 4], vec![5]]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `size`      - The maximum number of items in each chunk.
+    ///
+    /// # Panics
+    /// Panics if `size` is 0.
+    ///
+    #[cfg(feature = "alloc")]
+    fn chunks(self, size: usize) -> Adapter<(Self, usize), Vec<Self::Item>>
+    {
+        assert!(size != 0, "chunk size must be non-zero");
+        ParamFromFnIter::new(
+            (self, size),
+            |(iter, size)| {
+                let chunk = iter.by_ref().take(*size).collect::<Vec<_>>();
+                if chunk.is_empty() { None } else { Some(chunk) }
+            })
+    }
+
+    /// Like `.chunks()`, but collects each chunk into any `FromIterator` 
+    /// collection, such as a `String` of `char`s or a `HashSet` that drops 
+    /// repeats within a chunk. Needs no allocator of its own.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = "abcde".chars().collect_chunks_into::<String>(2)
+    ///                        .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec!["ab", "cd", "e"]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `size`      - The maximum number of items in each chunk.
+    ///
+    /// # Panics
+    /// Panics if `size` is 0.
+    ///
+    fn collect_chunks_into<C>(self, size: usize) -> Adapter<(Self, usize), C>
+    //
+    where C: FromIterator<Self::Item>,
+    {
+        assert!(size != 0, "chunk size must be non-zero");
+        ParamFromFnIter::new(
+            (self, size),
+            |(iter, size)| {
+                let first = iter.next()?;
+                Some(core::iter::once(first).chain(iter.take(*size - 1))
+                                            .collect())
+            })
+    }
+
+    /// Returns an iterator over overlapping windows of `size` consecutive 
+    /// items. The last `size` items are kept in a `VecDeque` and each window 
+    /// is yielded as a `Vec` snapshot as a new item slides in. Inputs shorter
+    /// than `size` yield nothing.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = (1..=4).windows(3).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![vec![1, 2, 3], vec![2, 3, 4]]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `size`      - The number of items in each window.
+    ///
+    /// # Panics
+    /// Panics if `size` is 0.
+    ///
+    #[cfg(feature = "alloc")]
+    fn windows(self, 
+               size: usize
+              ) -> Adapter<(Self, VecDeque<Self::Item>, usize), 
+                           Vec<Self::Item>>
+    //
+    where Self::Item: Clone,
+    {
+        assert!(size != 0, "window size must be non-zero");
+        ParamFromFnIter::new(
+            (self, VecDeque::with_capacity(size), size),
+            |(iter, window, size)| {
+                slide(iter, window, *size)?;
+                Some(window.iter().cloned().collect())
+            })
+    }
+
+    /// Returns an iterator that yields `sep` between every pair of adjacent 
+    /// items, but not before the first item or after the last.
+    ///
+    /// std has an unstable `Iterator::intersperse()` of the same name, so the
+    /// fully qualified syntax avoids the `unstable_name_collisions` lint.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let s = IterMapExt::intersperse("abc".chars(), '-').collect::<String>();
+    ///
+    /// assert_eq!(&s, "a-b-c");
+    /// ```
+    ///
+    /// # Arguments
+    /// * `sep`       - The separator to yield between items.
+    ///
+    fn intersperse(self, 
+                   sep: Self::Item
+                  ) -> Adapter<(Peekable<Self>, Self::Item, IntersperseState), 
+                               Self::Item>
+    //
+    where Self::Item: Clone,
+    {
+        ParamFromFnIter::new(
+            (self.peekable(), sep, IntersperseState::Item),
+            |(iter, sep, state)| {
+                match state {
+                    IntersperseState::Item => {
+                        let item = iter.next()?;
+                        *state = IntersperseState::Separator;
+                        Some(item)
+                    },
+                    IntersperseState::Separator => {
+                        iter.peek()?;
+                        *state = IntersperseState::Item;
+                        Some(sep.clone())
+                    },
+                }
+            })
+    }
+
+    /// Like `.intersperse()`, but each separator is produced by calling 
+    /// `sep_fn`. The closure is only called when a separator is actually 
+    /// yielded, so `n` items cause `n - 1` calls.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let mut n = 0;
+    /// let v = IterMapExt::intersperse_with(1..4, || { n += 10; n })
+    ///             .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 10, 2, 20, 3]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `sep_fn`    - The closure producing each separator.
+    ///
+    fn intersperse_with<G>(self, 
+                           sep_fn: G
+                          ) -> Adapter<(Peekable<Self>, G, IntersperseState), 
+                                       Self::Item>
+    //
+    where G: FnMut() -> Self::Item,
+    {
+        ParamFromFnIter::new(
+            (self.peekable(), sep_fn, IntersperseState::Item),
+            |(iter, sep_fn, state)| {
+                match state {
+                    IntersperseState::Item => {
+                        let item = iter.next()?;
+                        *state = IntersperseState::Separator;
+                        Some(item)
+                    },
+                    IntersperseState::Separator => {
+                        iter.peek()?;
+                        *state = IntersperseState::Item;
+                        Some(sep_fn())
+                    },
+                }
+            })
+    }
+
+    /// Returns an iterator that collapses runs of consecutive equal items 
+    /// into one. The last yielded item is kept in the iterator's data to 
+    /// compare against; the first item always passes through.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = [1, 1, 2, 3, 3, 3, 1].iter().dedup().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![&1, &2, &3, &1]);
+    /// ```
+    ///
+    fn dedup(self) -> Adapter<(Self, Option<Self::Item>), Self::Item>
+    //
+    where Self::Item: PartialEq + Clone,
+    {
+        ParamFromFnIter::new(
+            (self, None),
+            |(iter, last)| {
+                let item = iter.find(|item| last.as_ref() != Some(item))?;
+                *last = Some(item.clone());
+                Some(item)
+            })
+    }
+
+    /// Like `.dedup()`, but items are considered equal when `eq` returns 
+    /// true. `eq` is passed the last yielded item and the candidate.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = "aAbBa".chars()
+    ///                .dedup_by(|a, b| a.eq_ignore_ascii_case(b))
+    ///                .collect::<String>();
+    ///
+    /// assert_eq!(&v, "aba");
+    /// ```
+    ///
+    /// # Arguments
+    /// * `eq`        - The closure deciding whether two items are equal.
+    ///
+    fn dedup_by<G>(self, 
+                   eq: G
+                  ) -> Adapter<(Self, Option<Self::Item>, G), Self::Item>
+    //
+    where G: FnMut(&Self::Item, &Self::Item) -> bool,
+          Self::Item: Clone,
+    {
+        ParamFromFnIter::new(
+            (self, None, eq),
+            |(iter, last, eq)| {
+                let item = iter.find(|item| match last {
+                    Some(last) => !eq(last, item),
+                    None       => true,
+                })?;
+                *last = Some(item.clone());
+                Some(item)
+            })
+    }
+
+    /// Returns an iterator of `(count, item)` pairs, one for each run of 
+    /// consecutive equal items.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = "aabccc".chars().run_length_encode().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![(2, 'a'), (1, 'b'), (3, 'c')]);
+    /// ```
+    ///
+    fn run_length_encode(self) -> Adapter<Peekable<Self>, (usize, Self::Item)>
+    //
+    where Self::Item: PartialEq,
+    {
+        ParamFromFnIter::new(
+            self.peekable(),
+            |iter| {
+                let item = iter.next()?;
+                let mut count = 1;
+                while iter.next_if_eq(&item).is_some() {
+                    count += 1;
+                }
+                Some((count, item))
+            })
+    }
+
+    /// Returns an iterator grouping adjacent items that share a key. Each 
+    /// group is yielded as the key and a `Vec` of its items. Items with equal
+    /// keys that aren't adjacent form separate groups.
+    ///
+    /// `key_fn` is called again on the first item of each group after it was
+    /// used to end the previous one, so it should be cheap and pure.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = [1, 3, 2, 4, 5].iter()
+    ///                        .group_consecutive_by(|n| *n % 2)
+    ///                        .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![(1, vec![&1, &3]), 
+    ///                    (0, vec![&2, &4]), 
+    ///                    (1, vec![&5])]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `key_fn`    - The closure deriving each item's key.
+    ///
+    #[cfg(feature = "alloc")]
+    fn group_consecutive_by<K, G>(self, 
+                                  key_fn: G
+                                 ) -> Adapter<(Peekable<Self>, G), 
+                                              (K, Vec<Self::Item>)>
+    //
+    where G: FnMut(&Self::Item) -> K,
+          K: PartialEq,
+    {
+        ParamFromFnIter::new(
+            (self.peekable(), key_fn),
+            |(iter, key_fn)| {
+                let first = iter.next()?;
+                let key = key_fn(&first);
+                let mut group = vec![first];
+                while let Some(item) = iter.next_if(|it| key_fn(it) == key) {
+                    group.push(item);
+                }
+                Some((key, group))
+            })
+    }
+
+    /// Like `Iterator::scan()`, but the state lives in the iterator's data 
+    /// and can be recovered with `.into_state()` once iteration stops. `f` is
+    /// passed the state and each item; returning `None` ends iteration.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let mut iter = (1..=4).scan_map(0, |sum, n| { *sum += n; Some(*sum) });
+    ///
+    /// assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![1, 3, 6, 10]);
+    /// assert_eq!(iter.into_state(), 10);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `init`      - The initial state.
+    /// * `f`         - The closure invoked with the state and each item.
+    ///
+    fn scan_map<S, G, R>(self, 
+                         init : S, 
+                         f    : G
+                        ) -> Adapter<ScanState<Self, S, G>, R>
+    //
+    where G: FnMut(&mut S, Self::Item) -> Option<R>,
+    {
+        ParamFromFnIter::new(
+            ScanState { iter: self, state: init, f },
+            |ScanState { iter, state, f }| {
+                let item = iter.next()?;
+                f(state, item)
+            })
+    }
+
+    /// Returns an iterator of prefix reductions. Each item is folded into the
+    /// accumulator with `f`, and the new accumulator is yielded. The 
+    /// accumulator is kept in the iterator's data.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = (1..=4).accumulate(0, |sum, n| sum + n).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 3, 6, 10]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `init`      - The initial accumulator, which isn't yielded.
+    /// * `f`         - The closure producing the next accumulator from the 
+    ///                 current one and an item.
+    ///
+    fn accumulate<A, G>(self, init: A, f: G) -> Adapter<(Self, A, G), A>
+    //
+    where G: FnMut(&A, &Self::Item) -> A,
+          A: Clone,
+    {
+        ParamFromFnIter::new(
+            (self, init, f),
+            |(iter, acc, f)| {
+                let item = iter.next()?;
+                *acc = f(acc, &item);
+                Some(acc.clone())
+            })
+    }
+
+    /// Like `.accumulate()`, but `init` is yielded first, so the output is 
+    /// one item longer than the input.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = (1..=3).accumulate_from(0, |sum, n| sum + n)
+    ///                .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![0, 1, 3, 6]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `init`      - The initial accumulator, which is yielded first.
+    /// * `f`         - The closure producing the next accumulator from the 
+    ///                 current one and an item.
+    ///
+    fn accumulate_from<A, G>(self, 
+                             init : A, 
+                             f    : G
+                            ) -> Adapter<(Self, Option<A>, G), A>
+    //
+    where G: FnMut(&A, &Self::Item) -> A,
+    {
+        ParamFromFnIter::new(
+            (self, Some(init), f),
+            |(iter, acc, f)| {
+                let current = acc.take()?;
+                *acc = iter.next().map(|item| f(&current, &item));
+                Some(current)
+            })
+    }
+
+    /// Returns an iterator over each pair of adjacent items. The previous 
+    /// item is kept in the iterator's data and is primed by the first call to
+    /// `.next()`. Inputs with fewer than two items yield nothing.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = "abc".chars().pairwise().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![('a', 'b'), ('b', 'c')]);
+    /// ```
+    ///
+    fn pairwise(self) -> Adapter<(Self, Option<Self::Item>), 
+                                 (Self::Item, Self::Item)>
+    //
+    where Self::Item: Clone,
+    {
+        ParamFromFnIter::new(
+            (self, None),
+            |(iter, prev)| next_pair(iter, prev))
+    }
+
+    /// Returns an iterator tagging each item with its `Position` in the 
+    /// sequence. The last item is detected by peeking, so a single item is 
+    /// tagged `Position::Only`.
+    ///
+    /// ```
+    /// use iter_map::{IterMapExt, Position};
+    ///
+    /// let v = "abc".chars().with_position().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![(Position::First, 'a'), 
+    ///                    (Position::Middle, 'b'), 
+    ///                    (Position::Last, 'c')]);
+    /// ```
+    ///
+    fn with_position(self) -> Adapter<(Peekable<Self>, bool), 
+                                      (Position, Self::Item)>
+    {
+        ParamFromFnIter::new(
+            (self.peekable(), false),
+            |(iter, started)| {
+                let item = iter.next()?;
+                let position = match (*started, iter.peek().is_none()) {
+                    (false, true)  => Position::Only,
+                    (false, false) => Position::First,
+                    (true, false)  => Position::Middle,
+                    (true, true)   => Position::Last,
+                };
+                *started = true;
+                Some((position, item))
+            })
+    }
+
+    /// Returns an iterator that yields every item and then, if fewer than 
+    /// `min_len` items were produced, yields values from `fill` until there 
+    /// are `min_len` in total. `fill` is passed the index of the item it 
+    /// produces.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = (1..=2).pad_with(4, |_| 0).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 2, 0, 0]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `min_len`   - The minimum number of items to yield.
+    /// * `fill`      - The closure producing fill values from their index.
+    ///
+    fn pad_with<G>(self, 
+                   min_len : usize, 
+                   fill    : G
+                  ) -> Adapter<(Fuse<Self>, usize, usize, G), Self::Item>
+    //
+    where G: FnMut(usize) -> Self::Item,
+    {
+        ParamFromFnIter::new(
+            (self.fuse(), 0, min_len, fill),
+            |(iter, count, min_len, fill)| {
+                let item = match iter.next() {
+                    Some(item) => item,
+                    None if *count < *min_len => fill(*count),
+                    None => return None,
+                };
+                *count += 1;
+                Some(item)
+            })
+    }
+
+    /// Returns an iterator that yields items until `pred` returns true, 
+    /// yields that final item too, and then stops. Unlike `.take_while()`, 
+    /// the terminating item isn't lost.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = (1..).take_until_inclusive(|&n| n == 3).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 2, 3]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `pred`      - The predicate identifying the last item to yield.
+    ///
+    fn take_until_inclusive<G>(self, 
+                               pred: G
+                              ) -> Adapter<(Self, bool, G), Self::Item>
+    //
+    where G: FnMut(&Self::Item) -> bool,
+    {
+        ParamFromFnIter::new(
+            (self, false, pred),
+            |(iter, done, pred)| {
+                if *done {
+                    return None;
+                }
+                let item = iter.next()?;
+                *done = pred(&item);
+                Some(item)
+            })
+    }
+
+    /// Returns an iterator alternating between the items of `self` and 
+    /// `other`, starting with `self`. Once either is exhausted, the rest of 
+    /// the other is yielded.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = [1, 3, 5, 7].iter().interleave(&[2, 4]).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![&1, &2, &3, &4, &5, &7]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `other`     - The iterator to alternate with.
+    ///
+    fn interleave<J>(self, 
+                     other: J
+                    ) -> Adapter<(Fuse<Self>, Fuse<J::IntoIter>, bool), 
+                                 Self::Item>
+    //
+    where J: IntoIterator<Item = Self::Item>,
+    {
+        ParamFromFnIter::new(
+            (self.fuse(), other.into_iter().fuse(), false),
+            |(a, b, flip)| {
+                *flip = !*flip;
+                if *flip {
+                    a.next().or_else(|| b.next())
+                } else {
+                    b.next().or_else(|| a.next())
+                }
+            })
+    }
+
+    /// Returns an iterator pairing up the items of `self` and `other` like 
+    /// `.zip()`, but continuing until both are exhausted. Items without a 
+    /// partner are yielded as `Pair::Left` or `Pair::Right`.
+    ///
+    /// ```
+    /// use iter_map::{IterMapExt, Pair};
+    ///
+    /// let v = (1..=2).zip_longest("a".chars()).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![Pair::Both(1, 'a'), Pair::Left(2)]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `other`     - The iterator to pair up with.
+    ///
+    fn zip_longest<J>(self, 
+                      other: J
+                     ) -> Adapter<(Fuse<Self>, Fuse<J::IntoIter>), 
+                                  Pair<Self::Item, J::Item>>
+    //
+    where J: IntoIterator,
+    {
+        ParamFromFnIter::new(
+            (self.fuse(), other.into_iter().fuse()),
+            |(a, b)| {
+                match (a.next(), b.next()) {
+                    (Some(a), Some(b)) => Some(Pair::Both(a, b)),
+                    (Some(a), None)    => Some(Pair::Left(a)),
+                    (None, Some(b))    => Some(Pair::Right(b)),
+                    (None, None)       => None,
+                }
+            })
+    }
+
+    /// Returns an iterator with a variable stride. After each item is 
+    /// yielded, `f` decides how many items to skip before the next one; 0 
+    /// takes the very next item. The first item is always yielded.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = (0..10).step_fn(|&n| n).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![0, 1, 3, 7]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `f`         - The closure returning how many items to skip after 
+    ///                 the item it's passed.
+    ///
+    fn step_fn<G>(self, f: G) -> Adapter<(Self, usize, G), Self::Item>
+    //
+    where G: FnMut(&Self::Item) -> usize,
+    {
+        ParamFromFnIter::new(
+            (self, 0, f),
+            |(iter, skip, f)| {
+                let item = iter.nth(*skip)?;
+                *skip = f(&item);
+                Some(item)
+            })
+    }
+
+    /// Returns an iterator over the mean of each full window of `window` 
+    /// consecutive items, using the same sliding `VecDeque` as `.windows()`.
+    /// Inputs shorter than `window` yield nothing.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = [1, 2, 3, 4].iter().copied().moving_average(2)
+    ///                     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1.5, 2.5, 3.5]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `window`    - The number of items averaged together.
+    ///
+    /// # Panics
+    /// Panics if `window` is 0.
+    ///
+    #[cfg(feature = "alloc")]
+    fn moving_average(self, 
+                      window: usize
+                     ) -> Adapter<(Self, VecDeque<f64>, usize), f64>
+    //
+    where Self::Item: Into<f64>,
+    {
+        assert!(window != 0, "window size must be non-zero");
+        ParamFromFnIter::new(
+            (self, VecDeque::with_capacity(window), window),
+            |(iter, values, window)| {
+                slide(&mut iter.map(Into::into), values, *window)?;
+                Some(values.iter().sum::<f64>() / *window as f64)
+            })
+    }
+
+    /// Returns an iterator over the differences between adjacent items, 
+    /// `curr - prev`. Like `.pairwise()`, the previous item is kept in the 
+    /// iterator's data, so the output is one item shorter than the input.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = [1, 4, 9, 16].iter().copied().differences().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![3, 5, 7]);
+    /// ```
+    ///
+    fn differences(self) -> Adapter<(Self, Option<Self::Item>), Self::Item>
+    //
+    where Self::Item: Sub<Output = Self::Item> + Clone,
+    {
+        ParamFromFnIter::new(
+            (self, None),
+            |(iter, prev)| {
+                let (prev, curr) = next_pair(iter, prev)?;
+                Some(curr - prev)
+            })
+    }
+
+    /// Like `.enumerate()`, but the first index is `start`. The counter is 
+    /// kept in the iterator's data.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = "ab".chars().enumerate_from(1).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![(1, 'a'), (2, 'b')]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `start`     - The index of the first item.
+    ///
+    /// # Panics
+    /// Panics if an index past `usize::MAX` would be needed.
+    ///
+    fn enumerate_from(self, 
+                      start: usize
+                     ) -> Adapter<(Self, Option<usize>), (usize, Self::Item)>
+    {
+        ParamFromFnIter::new(
+            (self, Some(start)),
+            |(iter, index)| {
+                let item = iter.next()?;
+                let current = index.expect("enumerate_from index overflowed");
+                *index = current.checked_add(1);
+                Some((current, item))
+            })
+    }
+
+    /// Returns an iterator that yields the items `times` times over. The 
+    /// first pass buffers the items in a `Vec` in the iterator's data, and 
+    /// later passes replay the buffer, so the iterator itself needn't be 
+    /// `Clone`. `times` of 0 yields nothing and 1 passes items through 
+    /// without buffering.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = (1..=2).cycle_n(3).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 2, 1, 2, 1, 2]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `times`     - The number of passes over the items.
+    ///
+    #[cfg(feature = "alloc")]
+    fn cycle_n(self, 
+               times: usize
+              ) -> Adapter<(Fuse<Self>, Vec<Self::Item>, usize, usize), 
+                           Self::Item>
+    //
+    where Self::Item: Clone,
+    {
+        ParamFromFnIter::new(
+            (self.fuse(), Vec::new(), times, 0),
+            |(iter, buffer, passes, pos)| {
+                if *passes == 0 {
+                    return None;
+                }
+                if let Some(item) = iter.next() {
+                    if *passes > 1 {
+                        buffer.push(item.clone());
+                        *pos += 1;
+                    }
+                    return Some(item);
+                }
+                if *pos == buffer.len() {
+                    *passes -= 1;
+                    *pos = 0;
+                }
+                if *passes == 0 || buffer.is_empty() {
+                    return None;
+                }
+                let item = buffer[*pos].clone();
+                *pos += 1;
+                Some(item)
+            })
+    }
+
+    /// Returns an iterator that yields each item `n` times before moving on.
+    /// The current item and the number of repeats left are kept in the 
+    /// iterator's data; the last repeat moves the item out rather than 
+    /// cloning it. `n` of 0 yields nothing.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let s = "ab".chars().repeat_each(2).collect::<String>();
+    ///
+    /// assert_eq!(&s, "aabb");
+    /// ```
+    ///
+    /// # Arguments
+    /// * `n`         - The number of times each item is yielded.
+    ///
+    fn repeat_each(self, 
+                   n: usize
+                  ) -> Adapter<(Self, Option<Self::Item>, usize, usize), 
+                               Self::Item>
+    //
+    where Self::Item: Clone,
+    {
+        ParamFromFnIter::new(
+            (self, None, 0, n),
+            |(iter, current, remaining, n)| {
+                if *remaining == 0 {
+                    if *n == 0 {
+                        return None;
+                    }
+                    *current = Some(iter.next()?);
+                    *remaining = *n;
+                }
+                *remaining -= 1;
+                if *remaining == 0 { current.take() } else { current.clone() }
+            })
+    }
+
+    /// Returns an iterator over the contents of the `Some` items of an 
+    /// iterator of `Option`s, skipping the `None`s. Equivalent to 
+    /// `.filter_map(|x| x)` or `.flatten()`.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = ["1", "x", "3"].iter()
+    ///                        .map(|s| s.parse::<i32>().ok())
+    ///                        .flatten_options()
+    ///                        .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 3]);
+    /// ```
+    ///
+    fn flatten_options<T>(self) -> Adapter<Self, T>
+    //
+    where Self: Iterator<Item = Option<T>>,
+    {
+        ParamFromFnIter::new(
+            self,
+            |iter| {
+                for item in iter {
+                    if item.is_some() {
+                        return item;
+                    }
+                }
+                None
+            })
+    }
+
+    /// Returns an iterator that splits the items into `Vec` segments on each
+    /// item `is_delim` matches. Delimiters are dropped and not included in 
+    /// either neighbouring segment. A trailing delimiter produces a final 
+    /// empty segment, as `str::split()` does; an empty input produces none.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = "ab,,c".chars()
+    ///                .split_by(|c| *c == ',')
+    ///                .map(|s| s.into_iter().collect::<String>())
+    ///                .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec!["ab", "", "c"]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `is_delim`  - The predicate identifying delimiter items.
+    ///
+    #[cfg(feature = "alloc")]
+    fn split_by<G>(self, 
+                   is_delim: G
+                  ) -> Adapter<(Fuse<Self>, G, bool), Vec<Self::Item>>
+    //
+    where G: FnMut(&Self::Item) -> bool,
+    {
+        ParamFromFnIter::new(
+            (self.fuse(), is_delim, false),
+            |(iter, is_delim, pending)| {
+                let mut segment = Vec::new();
+                for item in iter.by_ref() {
+                    if is_delim(&item) {
+                        *pending = true;
+                        return Some(segment);
+                    }
+                    segment.push(item);
+                }
+                if segment.is_empty() && !*pending {
+                    return None;
+                }
+                *pending = false;
+                Some(segment)
+            })
+    }
+
+    /// Returns an iterator that batches consecutive items sharing the key 
+    /// `key_fn` derives, yielding `(key, batch)` each time the key changes 
+    /// and flushing the final partial batch when the input ends. This is the
+    /// same grouping `group_consecutive_by()` performs, named for the 
+    /// log-aggregation style of use, e.g. batching records by time bucket.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let stamps = [100, 105, 161, 170, 230];
+    /// let v = stamps.iter()
+    ///               .batch_by_key(|t| *t / 60)
+    ///               .map(|(minute, batch)| (minute, batch.len()))
+    ///               .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![(1, 2), (2, 2), (3, 1)]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `key_fn`    - The closure deriving each item's batch key.
+    ///
+    #[cfg(feature = "alloc")]
+    fn batch_by_key<K, G>(self, 
+                          key_fn: G
+                         ) -> Adapter<(Peekable<Self>, G), 
+                                      (K, Vec<Self::Item>)>
+    //
+    where G: FnMut(&Self::Item) -> K,
+          K: PartialEq,
+    {
+        self.group_consecutive_by(key_fn)
+    }
+
+    /// Returns an iterator that yields at most `n` items. The number left is
+    /// kept in the iterator's data, and once it reaches zero the inner 
+    /// iterator - and so any `iter_map()` callback driving it - is not 
+    /// advanced again.
+    ///
+    /// ```
+    /// use iter_map::{IntoIterMap, IterMapExt};
+    ///
+    /// let mut calls = 0;
+    /// let v = (1..).iter_map(|it| { calls += 1; it.next() })
+    ///              .cap(3)
+    ///              .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 2, 3]);
+    /// assert_eq!(calls, 3);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `n`         - The maximum number of items to yield.
+    ///
+    fn cap(self, n: usize) -> Adapter<(Self, usize), Self::Item>
+    {
+        ParamFromFnIter::new(
+            (self, n),
+            |(iter, left)| {
+                if *left == 0 {
+                    return None;
+                }
+                *left -= 1;
+                iter.next()
+            })
+    }
+
+    /// Like `Iterator::inspect()`, but `f` gets a `&mut` to each item and can
+    /// adjust it in place before it's yielded, e.g. to normalize values 
+    /// mid-pipeline without changing their type.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = ["a", "B", "c"].iter()
+    ///                        .map(|s| s.to_string())
+    ///                        .inspect_mut(|s| s.make_ascii_uppercase())
+    ///                        .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec!["A", "B", "C"]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `f`         - The closure invoked with a mutable ref to each item.
+    ///
+    fn inspect_mut<G>(self, f: G) -> Adapter<(Self, G), Self::Item>
+    //
+    where G: FnMut(&mut Self::Item),
+    {
+        ParamFromFnIter::new(
+            (self, f),
+            |(iter, f)| {
+                let mut item = iter.next()?;
+                f(&mut item);
+                Some(item)
+            })
+    }
+
+    /// Like `Iterator::filter()`, but the predicate is also passed a state 
+    /// it can update, so whether an item is kept can depend on the items seen
+    /// before it. The state lives in the iterator's data and can be recovered
+    /// with `.into_state()`.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// // Keep only items greater than every item seen so far.
+    /// let v = [3, 1, 4, 1, 5, 9, 2, 6].iter()
+    ///                                 .filter_with_state(None, |max, n| {
+    ///                                     let keep = *max < Some(**n);
+    ///                                     if keep { *max = Some(**n); }
+    ///                                     keep
+    ///                                 })
+    ///                                 .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![&3, &4, &5, &9]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `init`      - The initial state.
+    /// * `pred`      - The closure invoked with the state and each item. Items
+    ///                 it returns `true` for are yielded.
+    ///
+    fn filter_with_state<S, G>(self, 
+                               init : S, 
+                               pred : G
+                              ) -> Adapter<ScanState<Self, S, G>, Self::Item>
+    //
+    where G: FnMut(&mut S, &Self::Item) -> bool,
+    {
+        ParamFromFnIter::new(
+            ScanState { iter: self, state: init, f: pred },
+            |ScanState { iter, state, f }| iter.find(|item| f(state, item)))
+    }
+
+    /// Returns an iterator that applies `f` to each full window of `window`
+    /// consecutive items, using the same sliding `VecDeque` as `.windows()` 
+    /// but lending `f` a slice of it instead of cloning each window. This 
+    /// generalizes `.moving_average()` to arbitrary reductions. Inputs 
+    /// shorter than `window` yield nothing.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = "abcd".chars()
+    ///               .sliding_reduce(2, |w| w.iter().collect::<String>())
+    ///               .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec!["ab", "bc", "cd"]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `window`    - The number of items in each window.
+    /// * `f`         - The closure reducing each window to a value.
+    ///
+    /// # Panics
+    /// Panics if `window` is 0.
+    ///
+    #[cfg(feature = "alloc")]
+    fn sliding_reduce<G, R>(self, 
+                            window : usize, 
+                            f      : G
+                           ) -> Adapter<(Self, VecDeque<Self::Item>, usize, G),
+                                        R>
+    //
+    where G: FnMut(&[Self::Item]) -> R,
+    {
+        assert!(window != 0, "window size must be non-zero");
+        ParamFromFnIter::new(
+            (self, VecDeque::with_capacity(window), window, f),
+            |(iter, items, window, f)| {
+                slide(iter, items, *window)?;
+                Some(f(items.make_contiguous()))
+            })
+    }
+
+    /// Consumes the iterator and collects its items into a `HashMap` keyed 
+    /// by `key_fn`. When two items have the same key, the later one replaces
+    /// the earlier. Unlike the other methods of this trait, this isn't a lazy
+    /// adapter.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let map = ["apple", "bean", "avocado"].iter().index_by(|s| s.len());
+    ///
+    /// assert_eq!(map[&4], &"bean");
+    /// assert_eq!(map.len(), 3);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `key_fn`    - The closure deriving each item's key.
+    ///
+    #[cfg(feature = "std")]
+    fn index_by<K, G>(self, mut key_fn: G) -> HashMap<K, Self::Item>
+    //
+    where G: FnMut(&Self::Item) -> K,
+          K: Eq + Hash,
+    {
+        self.map(|item| (key_fn(&item), item)).collect()
+    }
+
+    /// Consumes the iterator and collects its items into a `HashMap` from 
+    /// each key `key_fn` derives to a `Vec` of the items with that key, in 
+    /// the order they were produced. Unlike `.group_consecutive_by()`, items
+    /// with the same key needn't be adjacent.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let map = (1..=6).group_into_map(|n| n % 3);
+    ///
+    /// assert_eq!(map[&0], vec![3, 6]);
+    /// assert_eq!(map[&1], vec![1, 4]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `key_fn`    - The closure deriving each item's key.
+    ///
+    #[cfg(feature = "std")]
+    fn group_into_map<K, G>(self, mut key_fn: G) -> HashMap<K, Vec<Self::Item>>
+    //
+    where G: FnMut(&Self::Item) -> K,
+          K: Eq + Hash,
+    {
+        let mut map = HashMap::new();
+        for item in self {
+            map.entry(key_fn(&item)).or_insert_with(Vec::new).push(item);
+        }
+        map
+    }
+
+    /// Returns an iterator over `Result`s that, on an `Err` `should_retry` 
+    /// accepts, pulls the next item in its place, up to `max_attempts` times
+    /// per item. This suits sources like a `.try_iter_map()` callback whose
+    /// errors are transient and which can be asked again. The error is 
+    /// yielded once the retries run out or if `should_retry` rejects it.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = vec![Err("busy"), Ok(1), Err("fatal"), Ok(2)];
+    /// let v = v.into_iter()
+    ///          .retry(3, |e| *e == "busy")
+    ///          .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![Ok(1), Err("fatal"), Ok(2)]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `max_attempts`  - The maximum number of retries for each item.
+    /// * `should_retry`  - The predicate deciding whether an error is 
+    ///                     transient.
+    ///
+    fn retry<T, E, G>(self, 
+                      max_attempts : usize, 
+                      should_retry : G
+                     ) -> Adapter<(Self, usize, G), Result<T, E>>
+    //
+    where Self: Iterator<Item = Result<T, E>>,
+          G: FnMut(&E) -> bool,
+    {
+        ParamFromFnIter::new(
+            (self, max_attempts, should_retry),
+            |(iter, max_attempts, should_retry)| {
+                let mut attempts = 0;
+                loop {
+                    match iter.next()? {
+                        Err(e) if attempts < *max_attempts 
+                               && should_retry(&e) => attempts += 1,
+                        result => return Some(result),
+                    }
+                }
+            })
+    }
+
+    /// Returns an iterator that merges adjacent items with `f`. `f` is passed
+    /// the item held so far and its successor; `Ok(merged)` holds the merged
+    /// item for the next comparison, and `Err((a, b))` yields `a` and holds 
+    /// `b`. The item held when the input ends is flushed.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = [1, 2, 4, 5, 7].iter()
+    ///                        .map(|&n| (n, n))
+    ///                        .coalesce(|a, b| if a.1 + 1 == b.0 { 
+    ///                                             Ok((a.0, b.1)) 
+    ///                                         } else { 
+    ///                                             Err((a, b)) 
+    ///                                         })
+    ///                        .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![(1, 2), (4, 5), (7, 7)]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `f`         - The closure that merges two items or hands them back.
+    ///
+    fn coalesce<G>(self, f: G) -> Adapter<(Self, Option<Self::Item>, G), 
+                                          Self::Item>
+    //
+    where G: FnMut(Self::Item, Self::Item) 
+                   -> Result<Self::Item, (Self::Item, Self::Item)>,
+    {
+        ParamFromFnIter::new(
+            (self, None, f),
+            |(iter, held, f)| {
+                let mut acc = held.take().or_else(|| iter.next())?;
+                for item in iter {
+                    match f(acc, item) {
+                        Ok(merged) => acc = merged,
+                        Err((a, b)) => {
+                            *held = Some(b);
+                            return Some(a);
+                        },
+                    }
+                }
+                Some(acc)
+            })
+    }
+
+    /// Pulls the first item eagerly and returns it along with a pass-through
+    /// `ParamFromFnIter` over the rest, or `None` if there is no first item.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let (header, rows) = "id,name\n1,a\n2,b".lines().first_and_rest()
+    ///                                         .unwrap();
+    ///
+    /// assert_eq!(header, "id,name");
+    /// assert_eq!(rows.collect::<Vec<_>>(), vec!["1,a", "2,b"]);
+    /// ```
+    ///
+    fn first_and_rest(mut self
+                     ) -> Option<(Self::Item, 
+                                  ParamFromFnIter<fn(&mut Self) 
+                                                     -> Option<Self::Item>, 
+                                                  Self>)>
+    {
+        let first = self.next()?;
+        Some((first, ParamFromFnIter::new_passthrough(self)))
+    }
+
+    /// Splits each item into a pair with `f` and returns two iterators that 
+    /// lazily iterate the left and right halves. Unlike `Iterator::unzip()`,
+    /// nothing is collected up front; halves are only buffered while the 
+    /// other side is behind, and not at all once it's dropped.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let pairs = ["a=1", "b=2"];
+    /// let (keys, values) = pairs.iter().unzip_map(|kv| (&kv[..1], &kv[2..]));
+    ///
+    /// assert_eq!(keys.collect::<Vec<_>>(), vec!["a", "b"]);
+    /// assert_eq!(values.collect::<Vec<_>>(), vec!["1", "2"]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `f`         - The closure splitting each item into a pair.
+    ///
+    #[cfg(feature = "alloc")]
+    fn unzip_map<A, B, G>(self, 
+                          f: G
+                         ) -> (UnzipLefts<core::iter::Map<Self, G>, A, B>, 
+                               UnzipRights<core::iter::Map<Self, G>, A, B>)
+    //
+    where G: FnMut(Self::Item) -> (A, B),
+    {
+        let shared = (self.map(f), VecDeque::new(), VecDeque::new());
+        let (lefts, rights) = SharedSide::pair(shared);
+        (unzip_lefts(lefts), unzip_rights(rights))
+    }
+
+    /// Returns an iterator over batches of items whose total weight, as given
+    /// by `weigh`, doesn't exceed `max_weight`. A batch is yielded as soon as
+    /// the next item would push it over the limit, and that item starts the 
+    /// next batch; an item heavier than `max_weight` forms a batch of its 
+    /// own. `weigh` is invoked once per item.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = ["ab", "cde", "f", "ghijk"].iter()
+    ///                                    .chunks_by_weight(4, |s| s.len())
+    ///                                    .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![vec![&"ab"], vec![&"cde", &"f"], vec![&"ghijk"]]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `max_weight`    - The maximum total weight of a batch.
+    /// * `weigh`         - The closure returning each item's weight.
+    ///
+    #[cfg(feature = "alloc")]
+    fn chunks_by_weight<G>(self, 
+                           max_weight : usize, 
+                           weigh      : G
+                          ) -> Adapter<(Self, 
+                                        Option<(Self::Item, usize)>, 
+                                        usize, 
+                                        G), 
+                                       Vec<Self::Item>>
+    //
+    where G: FnMut(&Self::Item) -> usize,
+    {
+        ParamFromFnIter::new(
+            (self, None, max_weight, weigh),
+            |(iter, held, max_weight, weigh)| {
+                let (first, mut total) = held.take().or_else(|| {
+                    iter.next().map(|item| { let w = weigh(&item); (item, w) })
+                })?;
+                let mut batch = vec![first];
+                for item in iter {
+                    let weight = weigh(&item);
+                    match total.checked_add(weight) {
+                        Some(sum) if sum <= *max_weight => {
+                            total = sum;
+                            batch.push(item);
+                        },
+                        _ => {
+                            *held = Some((item, weight));
+                            break;
+                        },
+                    }
+                }
+                Some(batch)
+            })
+    }
+
+    /// Like `.windows()`, but with the window size given as a const generic
+    /// parameter, so each window is yielded as a `[T; N]` array instead of a
+    /// `Vec`. This is a stable analog of std's unstable 
+    /// `Iterator::map_windows()`. Inputs shorter than `N` yield nothing.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = "abcd".chars().array_windows::<2>().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![['a', 'b'], ['b', 'c'], ['c', 'd']]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `N` is 0.
+    ///
+    #[cfg(feature = "alloc")]
+    fn array_windows<const N: usize>(self
+                                    ) -> Adapter<(Self, VecDeque<Self::Item>), 
+                                                 [Self::Item; N]>
+    //
+    where Self::Item: Clone,
+    {
+        assert!(N != 0, "window size must be non-zero");
+        ParamFromFnIter::new(
+            (self, VecDeque::with_capacity(N)),
+            |(iter, window)| {
+                slide(iter, window, N)?;
+                Some(core::array::from_fn(|i| window[i].clone()))
+            })
+    }
+
+    /// Returns an iterator that converts each item with `U::try_from()`, 
+    /// yielding the `Result` of each conversion.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = [1_i64, 300].iter().copied().try_convert::<u8>()
+    ///                     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v[0], Ok(1));
+    /// assert!(v[1].is_err());
+    /// ```
+    ///
+    fn try_convert<U>(self) -> Adapter<Self, Result<U, U::Error>>
+    //
+    where U: TryFrom<Self::Item>,
+    {
+        ParamFromFnIter::new(self, |iter| iter.next().map(U::try_from))
+    }
+
+    /// Like `.try_convert()`, but iteration stops after the first conversion
+    /// error, which is yielded once. Collecting into a `Result<Vec<U>, _>` 
+    /// from this adapter doesn't pull any items past the error.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let mut iter = [1_i64, -1, 2].iter()
+    ///                              .copied()
+    ///                              .try_convert_or_stop::<u8>();
+    ///
+    /// assert_eq!(iter.next(), Some(Ok(1)));
+    /// assert!(matches!(iter.next(), Some(Err(_))));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    ///
+    fn try_convert_or_stop<U>(self) -> Adapter<(Self, bool), 
+                                               Result<U, U::Error>>
+    //
+    where U: TryFrom<Self::Item>,
+    {
+        ParamFromFnIter::new(
+            (self, false),
+            |(iter, failed)| {
+                if *failed {
+                    return None;
+                }
+                let result = U::try_from(iter.next()?);
+                *failed = result.is_err();
+                Some(result)
+            })
+    }
+
+    /// Consumes the iterator, folding each chunk of `size` items with 
+    /// `fold_item`, starting each from `init_chunk()`, and then reducing the
+    /// chunk results with `combine`. Returns `None` for an empty input. 
+    /// Unlike the other methods of this trait, this isn't a lazy adapter.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let product_of_sums = (1..=6).chunked_fold(2, 
+    ///                                            || 0, 
+    ///                                            |sum, n| sum + n, 
+    ///                                            |a, b| a * b);
+    ///
+    /// assert_eq!(product_of_sums, Some(3 * 7 * 11));
+    /// ```
+    ///
+    /// # Arguments
+    /// * `size`          - The maximum number of items in each chunk.
+    /// * `init_chunk`    - The closure producing each chunk's initial value.
+    /// * `fold_item`     - The closure folding an item into a chunk's value.
+    /// * `combine`       - The closure combining two chunk results.
+    ///
+    /// # Panics
+    /// Panics if `size` is 0.
+    ///
+    #[cfg(feature = "alloc")]
+    fn chunked_fold<A, G, H, C>(self, 
+                                size           : usize, 
+                                mut init_chunk : G, 
+                                mut fold_item  : H, 
+                                combine        : C
+                               ) -> Option<A>
+    //
+    where G: FnMut() -> A,
+          H: FnMut(A, Self::Item) -> A,
+          C: FnMut(A, A) -> A,
+    {
+        self.chunks(size)
+            .map(|chunk| chunk.into_iter().fold(init_chunk(), &mut fold_item))
+            .reduce(combine)
+    }
+
+    /// Returns an iterator that yields each item only the first time it's 
+    /// seen, in first-seen order. Unlike `.dedup()`, repeats needn't be 
+    /// adjacent. A clone of every distinct item is kept in a `HashSet` in the
+    /// iterator's data, so memory grows with the number of distinct items.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let s = "mississippi".chars().distinct().collect::<String>();
+    ///
+    /// assert_eq!(&s, "misp");
+    /// ```
+    ///
+    #[cfg(feature = "std")]
+    fn distinct(self) -> Adapter<(Self, HashSet<Self::Item>), Self::Item>
+    //
+    where Self::Item: Eq + Hash + Clone,
+    {
+        ParamFromFnIter::new(
+            (self, HashSet::new()),
+            |(iter, seen)| iter.find(|item| seen.insert(item.clone())))
+    }
+
+    /// Like `.distinct()`, but items are compared by the key `key_fn` 
+    /// derives, so only the key needs to be `Hash`. Each item whose key is 
+    /// newly seen is yielded; the keys are kept in a `HashSet` in the 
+    /// iterator's data.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = ["apple", "avocado", "bean"].iter()
+    ///                                     .distinct_by(|s| s.as_bytes()[0])
+    ///                                     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![&"apple", &"bean"]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `key_fn`    - The closure deriving each item's key.
+    ///
+    #[cfg(feature = "std")]
+    fn distinct_by<K, G>(self, 
+                         key_fn: G
+                        ) -> Adapter<(Self, HashSet<K>, G), Self::Item>
+    //
+    where G: FnMut(&Self::Item) -> K,
+          K: Eq + Hash,
+    {
+        ParamFromFnIter::new(
+            (self, HashSet::new(), key_fn),
+            |(iter, seen, key_fn)| iter.find(|item| seen.insert(key_fn(item))))
+    }
+
+    /// Returns an iterator over the zero-based indices of the items `pred` 
+    /// matches. The running index is kept in the iterator's data and 
+    /// advances for every item, matched or not.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = "a b c".chars().positions(|c| *c == ' ').collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 3]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `pred`      - The predicate selecting the items to report.
+    ///
+    fn positions<G>(self, pred: G) -> Adapter<(Self, usize, G), usize>
+    //
+    where G: FnMut(&Self::Item) -> bool,
+    {
+        ParamFromFnIter::new(
+            (self, 0, pred),
+            |(iter, index, pred)| {
+                for item in iter {
+                    *index += 1;
+                    if pred(&item) {
+                        return Some(*index - 1);
+                    }
+                }
+                None
+            })
+    }
+
+    /// Like `Iterator::filter_map()`, but counts the items pulled from the 
+    /// source, whether `f` extracted a value from them or not. The count 
+    /// lives in the iterator's data and can be recovered with 
+    /// `.into_attempts()`.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let mut iter = ["1", "two", "3"].iter()
+    ///                                 .extract_map(|s| s.parse::<i32>().ok());
+    ///
+    /// assert_eq!(iter.by_ref().sum::<i32>(), 4);
+    /// assert_eq!(iter.into_attempts(), 3);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `f`         - The closure extracting a value from each item.
+    ///
+    fn extract_map<G, R>(self, f: G) -> Adapter<ExtractState<Self, G>, R>
+    //
+    where G: FnMut(Self::Item) -> Option<R>,
+    {
+        ParamFromFnIter::new(
+            ExtractState { iter: self, f, attempts: 0 },
+            |ExtractState { iter, f, attempts }| {
+                for item in iter {
+                    *attempts += 1;
+                    if let Some(value) = f(item) {
+                        return Some(value);
+                    }
+                }
+                None
+            })
+    }
+
+    /// Returns two iterators that each yield every item. The items one side
+    /// has yielded but the other hasn't are buffered as clones, so if one 
+    /// side runs far ahead of the other, the buffer grows without bound. Once
+    /// one side is dropped, the other stops buffering.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let (a, b) = (1..=3).tee();
+    ///
+    /// assert_eq!(a.sum::<i32>(), 6);
+    /// assert_eq!(b.max(), Some(3));
+    /// ```
+    ///
+    #[cfg(feature = "alloc")]
+    fn tee(self) -> (Tee<Self>, Tee<Self>)
+    //
+    where Self::Item: Clone,
+    {
+        let (a, b) = SharedSide::pair((self, [VecDeque::new(), 
+                                              VecDeque::new()]));
+        (tee_side(a), tee_side(b))
+    }
+
+    /// Returns an iterator that invokes `f` once, the first time the inner 
+    /// iterator returns `None`, e.g. to close a resource the items were read
+    /// from. `f` isn't invoked if the iterator is dropped before it's 
+    /// exhausted; see `.on_drop()` for that.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let mut log = Vec::new();
+    /// let n = (0..3).on_exhausted(|| log.push("done")).count();
+    ///
+    /// assert_eq!(n, 3);
+    /// assert_eq!(log, vec!["done"]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `f`         - The closure invoked when the items run out.
+    ///
+    fn on_exhausted<G>(self, f: G) -> Adapter<(Self, Option<G>), Self::Item>
+    //
+    where G: FnOnce(),
+    {
+        ParamFromFnIter::new(
+            (self, Some(f)),
+            |(iter, f)| {
+                let item = iter.next();
+                if item.is_none() {
+                    if let Some(f) = f.take() {
+                        f();
+                    }
+                }
+                item
+            })
+    }
+
+    /// Returns an iterator that invokes `f` when it's dropped, whether or not
+    /// it was exhausted. The closure is kept in an `OnDrop` guard in the 
+    /// iterator's data.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    /// use std::cell::Cell;
+    ///
+    /// let closed = Cell::new(false);
+    /// let first = (0..3).on_drop(|| closed.set(true)).next();
+    ///
+    /// assert_eq!(first, Some(0));
+    /// assert!(closed.get());
+    /// ```
+    ///
+    /// # Arguments
+    /// * `f`         - The closure invoked when the iterator is dropped.
+    ///
+    fn on_drop<G>(self, f: G) -> Adapter<(Self, OnDrop<G>), Self::Item>
+    //
+    where G: FnOnce(),
+    {
+        ParamFromFnIter::new((self, OnDrop { f: Some(f) }), |(iter, _)| {
+            iter.next()
+        })
+    }
+
+    /// Returns an iterator over the items at indices `0, n, 2n, ...`, like 
+    /// `Iterator::step_by()`. The running index is kept in the iterator's 
+    /// data, and every item of the source is pulled through `.next()`, so an
+    /// `iter_map()` callback sees all of them.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let s = "a1b2c3".chars().every_nth(2).collect::<String>();
+    ///
+    /// assert_eq!(&s, "abc");
+    /// ```
+    ///
+    /// # Arguments
+    /// * `n`         - The distance between yielded items.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0.
+    ///
+    fn every_nth(self, n: usize) -> Adapter<(Self, usize, usize), Self::Item>
+    {
+        assert!(n != 0, "step must be non-zero");
+        ParamFromFnIter::new(
+            (self, 0, n),
+            |(iter, index, n)| {
+                for item in iter {
+                    *index += 1;
+                    if (*index - 1) % *n == 0 {
+                        return Some(item);
+                    }
+                }
+                None
+            })
+    }
+
+    /// Returns an iterator merging `self` and `other`, which are both assumed
+    /// to be sorted, into one sorted sequence. The heads of the two inputs 
+    /// are compared at each step. The merge is stable: on ties, the item from
+    /// `self` comes first.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = [1, 3, 5].iter().merge_sorted(&[2, 4, 6]).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![&1, &2, &3, &4, &5, &6]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `other`     - The sorted items to merge with `self`.
+    ///
+    fn merge_sorted<J>(self, 
+                       other: J
+                      ) -> Adapter<(Peekable<Self>, 
+                                    Peekable<J::IntoIter>, 
+                                    fn(&Self::Item, &Self::Item) -> Ordering), 
+                                   Self::Item>
+    //
+    where J: IntoIterator<Item = Self::Item>,
+          Self::Item: Ord,
+    {
+        self.merge_sorted_by(other, Ord::cmp)
+    }
+
+    /// Like `.merge_sorted()`, but ordered by the comparator `cmp`, which both
+    /// inputs are assumed to be sorted by.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let by_len_desc = |a: &&&str, b: &&&str| b.len().cmp(&a.len());
+    /// let v = ["ccc", "a"].iter()
+    ///                     .merge_sorted_by(&["bb"], by_len_desc)
+    ///                     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![&"ccc", &"bb", &"a"]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `other`     - The sorted items to merge with `self`.
+    /// * `cmp`       - The comparator both inputs are sorted by.
+    ///
+    fn merge_sorted_by<J, G>(self, 
+                             other : J, 
+                             cmp   : G
+                            ) -> Adapter<(Peekable<Self>, 
+                                          Peekable<J::IntoIter>, 
+                                          G), 
+                                         Self::Item>
+    //
+    where J: IntoIterator<Item = Self::Item>,
+          G: FnMut(&Self::Item, &Self::Item) -> Ordering,
+    {
+        ParamFromFnIter::new(
+            (self.peekable(), other.into_iter().peekable(), cmp),
+            |(a, b, cmp)| {
+                let take_a = match (a.peek(), b.peek()) {
+                    (Some(x), Some(y)) => cmp(x, y) != Ordering::Greater,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                };
+                if take_a { a.next() } else { b.next() }
+            })
+    }
+
+    /// Returns an iterator that replaces the items `pred` matches with 
+    /// `f(item)` and passes the others through unchanged.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = (1..=5).map_if(|n| n % 2 == 0, |n| -n).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, -2, 3, -4, 5]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `pred`      - The predicate selecting the items to transform.
+    /// * `f`         - The closure transforming the selected items.
+    ///
+    fn map_if<P, G>(self, pred: P, f: G) -> Adapter<(Self, P, G), Self::Item>
+    //
+    where P: FnMut(&Self::Item) -> bool,
+          G: FnMut(Self::Item) -> Self::Item,
+    {
+        ParamFromFnIter::new(
+            (self, pred, f),
+            |(iter, pred, f)| {
+                let item = iter.next()?;
+                Some(if pred(&item) { f(item) } else { item })
+            })
+    }
+
+    /// Returns an iterator over the CRC-32 (IEEE, as used by zlib and PNG) of
+    /// the bytes seen so far, yielding the updated checksum after each byte.
+    /// The checksum register is kept in the iterator's data; `.last()` gives
+    /// the checksum of the whole input.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let crc = b"123456789".iter().copied().running_checksum().last();
+    ///
+    /// assert_eq!(crc, Some(0xCBF4_3926));
+    /// ```
+    ///
+    fn running_checksum(self) -> Adapter<(Self, u32), u32>
+    //
+    where Self: Iterator<Item = u8>,
+    {
+        ParamFromFnIter::new(
+            (self, !0),
+            |(iter, crc)| {
+                *crc = crc32_update(*crc, iter.next()?);
+                Some(!*crc)
+            })
+    }
+
+    /// Returns an iterator over `f(&item)` that remembers the results for the
+    /// `cap` most recently used inputs, so repeated inputs don't invoke `f` 
+    /// again while they're in the cache. The cache is a small LRU kept in a 
+    /// `VecDeque` in the iterator's data and is searched linearly, which 
+    /// suits small values of `cap`. With `cap` of 0, nothing is cached.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let mut calls = 0;
+    /// let v = [2, 3, 2, 2].iter()
+    ///                     .cached_map(4, |n| { calls += 1; *n * 100 })
+    ///                     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![200, 300, 200, 200]);
+    /// assert_eq!(calls, 2);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `cap`       - The maximum number of inputs to remember.
+    /// * `f`         - The closure mapping each input to its result.
+    ///
+    #[cfg(feature = "alloc")]
+    fn cached_map<G, R>(self, 
+                        cap : usize, 
+                        f   : G
+                       ) -> Adapter<(Self, 
+                                     VecDeque<(Self::Item, R)>, 
+                                     usize, 
+                                     G), 
+                                    R>
+    //
+    where G: FnMut(&Self::Item) -> R,
+          Self::Item: Eq,
+          R: Clone,
+    {
+        ParamFromFnIter::new(
+            (self, VecDeque::with_capacity(cap), cap, f),
+            |(iter, cache, cap, f)| {
+                let item = iter.next()?;
+                if let Some(i) = cache.iter().position(|(k, _)| *k == item) {
+                    let entry = cache.remove(i)?;
+                    let result = entry.1.clone();
+                    cache.push_back(entry);
+                    return Some(result);
+                }
+                let result = f(&item);
+                if *cap > 0 {
+                    if cache.len() == *cap {
+                        cache.pop_front();
+                    }
+                    cache.push_back((item, result.clone()));
+                }
+                Some(result)
+            })
+    }
+
+    /// Returns an iterator over `f(&item, next)`, where `next` is a peek at 
+    /// the item that follows, or `None` for the last item. The inner iterator
+    /// is kept `Peekable` in the iterator's data; peeking doesn't consume the
+    /// next item.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let s = "abc".chars()
+    ///              .peek_map(|c, next| if next.is_some() { 
+    ///                                      format!("{}-", c) 
+    ///                                  } else { 
+    ///                                      c.to_string() 
+    ///                                  })
+    ///              .collect::<String>();
+    ///
+    /// assert_eq!(&s, "a-b-c");
+    /// ```
+    ///
+    /// # Arguments
+    /// * `f`         - The closure invoked with each item and the next one.
+    ///
+    fn peek_map<G, R>(self, f: G) -> Adapter<(Peekable<Self>, G), R>
+    //
+    where G: FnMut(&Self::Item, Option<&Self::Item>) -> R,
+    {
+        ParamFromFnIter::new(
+            (self.peekable(), f),
+            |(iter, f)| {
+                let item = iter.next()?;
+                Some(f(&item, iter.peek()))
+            })
+    }
+
+    /// Returns an iterator that yields items at least `interval` apart. Each
+    /// `.next()` after the first sleeps the current thread, as needed, until
+    /// `interval` has passed since the previous item was yielded, and only 
+    /// then pulls the next item, which suits polling a source. The time of 
+    /// the last yield is kept in the iterator's data.
+    ///
+    /// This blocks the calling thread, so it isn't suitable for async code.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let start = Instant::now();
+    /// let n = (0..3).throttle(Duration::from_millis(5)).count();
+    ///
+    /// assert_eq!(n, 3);
+    /// assert!(start.elapsed() >= Duration::from_millis(10));
+    /// ```
+    ///
+    /// # Arguments
+    /// * `interval`  - The minimum time between yielded items.
+    ///
+    #[cfg(feature = "std")]
+    fn throttle(self, 
+                interval: Duration
+               ) -> Adapter<(Self, Option<Instant>, Duration), Self::Item>
+    {
+        ParamFromFnIter::new(
+            (self, None, interval),
+            |(iter, last, interval)| {
+                if let Some(last) = last {
+                    let elapsed = last.elapsed();
+                    if elapsed < *interval {
+                        std::thread::sleep(*interval - elapsed);
+                    }
+                }
+                let item = iter.next()?;
+                *last = Some(Instant::now());
+                Some(item)
+            })
+    }
+
+    /// Returns an iterator over the items whose matching item in `selectors`
+    /// is `true`, like Python's `itertools.compress()`. Iteration stops when
+    /// either input runs out.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let s = "abcd".chars().compress(vec![true, false, true, false])
+    ///                       .collect::<String>();
+    ///
+    /// assert_eq!(&s, "ac");
+    /// ```
+    ///
+    /// # Arguments
+    /// * `selectors` - The flags selecting which items are yielded.
+    ///
+    fn compress<S>(self, 
+                   selectors: S
+                  ) -> Adapter<(Self, S::IntoIter), Self::Item>
+    //
+    where S: IntoIterator<Item = bool>,
+    {
+        ParamFromFnIter::new(
+            (self, selectors.into_iter()),
+            |(iter, selectors)| {
+                loop {
+                    let item = iter.next()?;
+                    if selectors.next()? {
+                        return Some(item);
+                    }
+                }
+            })
+    }
+
+    /// Splits the items where `pred` first returns `true`, returning lazy 
+    /// iterators over the leading run of items it rejects and over the rest.
+    /// `pred` is assumed to be monotone, `false` for a prefix of the items and
+    /// `true` from then on, as for sorted input; this isn't validated, and 
+    /// the second iterator yields everything after the split unchecked.
+    ///
+    /// Both halves share the source in an `Rc<RefCell<_>>`. Pulling from the
+    /// second half first buffers the rest of the leading run for the first.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let (small, large) = [1, 4, 6, 9].iter()
+    ///                                   .split_at_predicate(|n| **n > 5);
+    ///
+    /// assert_eq!(large.collect::<Vec<_>>(), vec![&6, &9]);
+    /// assert_eq!(small.collect::<Vec<_>>(), vec![&1, &4]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `pred`      - The predicate that's `true` from the split point on.
+    ///
+    #[cfg(feature = "alloc")]
+    fn split_at_predicate<G>(self, 
+                             pred: G
+                            ) -> (SplitHalf<Self, G>, SplitHalf<Self, G>)
+    //
+    where G: FnMut(&Self::Item) -> bool,
+    {
+        let shared = (self.peekable(), pred, VecDeque::new(), false);
+        let shared = Rc::new(RefCell::new(shared));
+        (split_before(shared.clone()), split_after(shared))
+    }
+
+    /// Like `.take_while(pred)` followed by `.every_nth(step)`: yields the 
+    /// items at indices `0, step, 2 * step, ...` of the leading run of items
+    /// `pred` accepts, and ends at the first item it rejects. The index 
+    /// within the run and whether it has ended are kept in the iterator's 
+    /// data.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = [1, 2, 3, 4, 5, 0, 7].iter()
+    ///                              .take_while_step(2, |n| **n > 0)
+    ///                              .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![&1, &3, &5]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `step`      - The distance between yielded items within the run.
+    /// * `pred`      - The predicate the leading run satisfies.
+    ///
+    /// # Panics
+    /// Panics if `step` is 0.
+    ///
+    fn take_while_step<G>(self, 
+                          step : usize, 
+                          pred : G
+                         ) -> Adapter<(Self, usize, usize, G, bool), 
+                                      Self::Item>
+    //
+    where G: FnMut(&Self::Item) -> bool,
+    {
+        assert!(step != 0, "step must be non-zero");
+        ParamFromFnIter::new(
+            (self, 0, step, pred, false),
+            |(iter, index, step, pred, done)| {
+                while !*done {
+                    let item = iter.next()?;
+                    if !pred(&item) {
+                        *done = true;
+                        break;
+                    }
+                    *index += 1;
+                    if (*index - 1) % *step == 0 {
+                        return Some(item);
+                    }
+                }
+                None
+            })
+    }
+
+    /// Returns an iterator driven by a state machine that turns each item 
+    /// into zero or more outputs, like a finite-state transducer. `step` is 
+    /// passed the state and each item, and `finish` is passed the final state
+    /// once the input ends, to flush any output still held back. Both return
+    /// any `IntoIterator` of outputs, such as an `Option`, an array, or a 
+    /// `Vec`; the outputs not yet yielded are kept in the iterator's data.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// // Sum each run of numbers between zeros, dropping the zeros.
+    /// let v = [1, 2, 0, 3, 0, 4, 5].iter()
+    ///                              .transduce(0, 
+    ///                                         |sum, &n| if n == 0 {
+    ///                                             Some(std::mem::take(sum))
+    ///                                         } else {
+    ///                                             *sum += n;
+    ///                                             None
+    ///                                         }, 
+    ///                                         Some)
+    ///                              .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![3, 3, 9]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `init`      - The initial state.
+    /// * `step`      - The closure turning the state and an item into outputs.
+    /// * `finish`    - The closure turning the final state into outputs.
+    ///
+    fn transduce<S, G, H, O>(self, 
+                             init   : S, 
+                             step   : G, 
+                             finish : H
+                            ) -> Adapter<(Self, 
+                                          Option<S>, 
+                                          G, 
+                                          Option<H>, 
+                                          Option<O::IntoIter>), 
+                                         O::Item>
+    //
+    where G: FnMut(&mut S, Self::Item) -> O,
+          H: FnOnce(S) -> O,
+          O: IntoIterator,
+    {
+        ParamFromFnIter::new(
+            (self, Some(init), step, Some(finish), None),
+            |(iter, state, step, finish, pending)| {
+                loop {
+                    if let Some(out) = pending {
+                        if let Some(item) = out.next() {
+                            return Some(item);
+                        }
+                        *pending = None;
+                    }
+                    let outputs = match iter.next() {
+                        Some(item) => step(state.as_mut()?, item),
+                        None => finish.take()?(state.take()?),
+                    };
+                    *pending = Some(outputs.into_iter());
+                }
+            })
+    }
+
+    /// Returns an iterator that suppresses any item equal to one of the last
+    /// `window` items it yielded. Unlike `.distinct()`, items fall out of the
+    /// window as new ones are yielded, so memory stays bounded by `window`.
+    /// The yielded items are kept in a `VecDeque` in the iterator's data,
+    /// along with a `HashMap` counting them for constant-time lookups.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let s = "abcacbdb".chars().dedup_within(2).collect::<String>();
+    ///
+    /// assert_eq!(&s, "abcabd");
+    /// ```
+    ///
+    /// # Arguments
+    /// * `window`    - The number of recently yielded items to compare with.
+    ///
+    #[cfg(feature = "std")]
+    fn dedup_within(self, 
+                    window: usize
+                   ) -> Adapter<(Self, 
+                                 VecDeque<Self::Item>, 
+                                 HashMap<Self::Item, usize>, 
+                                 usize), 
+                                Self::Item>
+    //
+    where Self::Item: Eq + Hash + Clone,
+    {
+        ParamFromFnIter::new(
+            (self, VecDeque::with_capacity(window), HashMap::new(), window),
+            |(iter, recent, counts, window)| {
+                let item = iter.find(|item| !counts.contains_key(item))?;
+                if *window > 0 {
+                    if recent.len() == *window {
+                        let old = recent.pop_front()?;
+                        if let Some(n) = counts.get_mut(&old) {
+                            *n -= 1;
+                            if *n == 0 {
+                                counts.remove(&old);
+                            }
+                        }
+                    }
+                    *counts.entry(item.clone()).or_insert(0) += 1;
+                    recent.push_back(item.clone());
+                }
+                Some(item)
+            })
+    }
+
+    /// Returns an iterator that yields `item` before the items of this one.
+    /// This is like chaining `core::iter::once(item)` in front, but the 
+    /// result is a `ParamFromFnIter`, so the data accessors remain available.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = (2..4).prepend(1).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 2, 3]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `item`      - The item to yield first.
+    ///
+    fn prepend(self, 
+               item: Self::Item
+              ) -> Adapter<(Self, ExtraState<Self::Item>), Self::Item>
+    {
+        ParamFromFnIter::new((self, ExtraState::Pending(item)), 
+                             |(iter, extra)| {
+                                 extra.take().or_else(|| iter.next())
+                             })
+    }
+
+    /// Returns an iterator that yields `item` after the items of this one.
+    /// Once `item` has been yielded, the iterator returns `None` without 
+    /// pulling from this one again.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = (1..3).append(3).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 2, 3]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `item`      - The item to yield last.
+    ///
+    fn append(self, 
+              item: Self::Item
+             ) -> Adapter<(Self, ExtraState<Self::Item>), Self::Item>
+    {
+        ParamFromFnIter::new((self, ExtraState::Pending(item)), 
+                             |(iter, extra)| {
+                                 match extra {
+                                     ExtraState::Pending(_) => {
+                                         iter.next().or_else(|| extra.take())
+                                     },
+                                     ExtraState::Yielded => None,
+                                 }
+                             })
+    }
+
+    /// Like `.scan_map()`, but ends for good at the first `None` `f` returns,
+    /// the way `.map_while()` does, without pulling from this iterator 
+    /// again. The item passed to that call is consumed. The state is kept in
+    /// the iterator's data and can be recovered with `.into_state()`.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let mut iter = (1..).map_while_state(0, |sum, n| {
+    ///     *sum += n;
+    ///     if *sum > 10 { None } else { Some(*sum) }
+    /// });
+    ///
+    /// assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![1, 3, 6, 10]);
+    /// assert_eq!(iter.next(), None);
+    /// assert_eq!(iter.into_state(), 15);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `init`      - The initial state.
+    /// * `f`         - The closure invoked with the state and each item.
+    ///
+    fn map_while_state<S, G, R>(self, 
+                                init : S, 
+                                f    : G
+                               ) -> Adapter<ScanState<Self, S, Option<G>>, R>
+    //
+    where G: FnMut(&mut S, Self::Item) -> Option<R>,
+    {
+        ParamFromFnIter::new(
+            ScanState { iter: self, state: init, f: Some(f) },
+            |ScanState { iter, state, f }| {
+                let out = f.as_mut()?(state, iter.next()?);
+                if out.is_none() {
+                    *f = None;
+                }
+                out
+            })
+    }
+
+    /// Groups time-sorted items into fixed-width time buckets, yielding each
+    /// non-empty bucket as its start time and a `Vec` of its items. Buckets
+    /// are laid out every `bucket` from the first item's timestamp, so a gap
+    /// with no items skips ahead to the bucket the next item falls in, and 
+    /// an item earlier than its predecessor joins the current bucket. The 
+    /// source, kept in the iterator's data as a `Peekable`, is read one item
+    /// past the end of each bucket.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let t0 = Instant::now();
+    /// let secs = [0, 1, 2, 5];
+    /// let v = secs.iter()
+    ///             .bucket_by_time(Duration::from_secs(2), 
+    ///                             |s| t0 + Duration::from_secs(**s))
+    ///             .map(|(start, items)| (start - t0, items))
+    ///             .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![(Duration::from_secs(0), vec![&0, &1]), 
+    ///                    (Duration::from_secs(2), vec![&2]), 
+    ///                    (Duration::from_secs(4), vec![&5])]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `bucket`    - The width of each time bucket.
+    /// * `ts`        - The closure returning an item's timestamp.
+    ///
+    /// # Panics
+    /// Panics if `bucket` is zero.
+    ///
+    #[cfg(feature = "std")]
+    fn bucket_by_time<G>(self, 
+                         bucket : Duration, 
+                         ts     : G
+                        ) -> Adapter<(Peekable<Self>, 
+                                      Option<Instant>, 
+                                      Duration, 
+                                      G), 
+                                     (Instant, Vec<Self::Item>)>
+    //
+    where G: FnMut(&Self::Item) -> Instant,
+    {
+        assert!(!bucket.is_zero(), "bucket width must be non-zero");
+        ParamFromFnIter::new(
+            (self.peekable(), None, bucket, ts),
+            |(iter, start, bucket, ts)| {
+                let first = iter.next()?;
+                let t = ts(&first);
+                let begin = match *start {
+                    Some(prev) => {
+                        let offset = t.saturating_duration_since(prev)
+                                      .as_nanos() % bucket.as_nanos();
+                        t - Duration::new((offset / 1_000_000_000) as u64, 
+                                          (offset % 1_000_000_000) as u32)
+                    },
+                    None => t,
+                };
+                let end = begin + *bucket;
+                let mut items = vec![first];
+                while let Some(item) = iter.next_if(|item| ts(item) < end) {
+                    items.push(item);
+                }
+                *start = Some(begin);
+                Some((begin, items))
+            })
+    }
+
+    /// Returns an iterator over the `Ok` values of an iterator of `Result`s 
+    /// that pushes each `Err` into `sink` and carries on, instead of stopping
+    /// as `.try_iter_map()` does. Keep a clone of `sink` to inspect the 
+    /// errors once the good items have been processed. `sink` is kept in the
+    /// iterator's data.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// let errors = Rc::new(RefCell::new(Vec::new()));
+    /// let v = ["1", "x", "3"].iter()
+    ///                        .map(|s| s.parse::<i32>().map_err(|_| *s))
+    ///                        .collect_errors(errors.clone())
+    ///                        .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 3]);
+    /// assert_eq!(*errors.borrow(), vec!["x"]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `sink`      - The shared `Vec` the errors are pushed into.
+    ///
+    #[cfg(feature = "alloc")]
+    fn collect_errors<T, E>(self, 
+                            sink: Rc<RefCell<Vec<E>>>
+                           ) -> Adapter<(Self, Rc<RefCell<Vec<E>>>), T>
+    //
+    where Self: Iterator<Item = Result<T, E>>,
+    {
+        ParamFromFnIter::new(
+            (self, sink),
+            |(iter, sink)| {
+                for item in iter {
+                    match item {
+                        Ok(value) => return Some(value),
+                        Err(e) => sink.borrow_mut().push(e),
+                    }
+                }
+                None
+            })
+    }
+
+    /// Transposes an iterator of equal-length rows into an iterator of 
+    /// columns. Every row has to be seen before the first column is known, 
+    /// so the first `.next()` collects the rows into the iterator's data, 
+    /// along with the index of the next column to yield.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = vec![vec![1, 2], vec![3, 4]].into_iter()
+    ///                                     .transpose()
+    ///                                     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![vec![1, 3], vec![2, 4]]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics on the first `.next()` if the rows differ in length.
+    ///
+    #[cfg(feature = "alloc")]
+    fn transpose<T>(self) -> Adapter<(Self, Option<Vec<Vec<T>>>, usize), 
+                                     Vec<T>>
+    //
+    where Self: Iterator<Item = Vec<T>>,
+          T: Clone,
+    {
+        ParamFromFnIter::new(
+            (self, None, 0),
+            |(iter, rows, column)| {
+                let rows = rows.get_or_insert_with(|| {
+                    let rows = iter.collect::<Vec<_>>();
+                    if let Some(first) = rows.first() {
+                        assert!(rows.iter().all(|r| r.len() == first.len()), 
+                                "rows must all have the same length");
+                    }
+                    rows
+                });
+                if *column >= rows.first()?.len() {
+                    return None;
+                }
+                *column += 1;
+                Some(rows.iter().map(|r| r[*column - 1].clone()).collect())
+            })
+    }
+
+    /// Returns an iterator yielding the smallest and largest items seen so 
+    /// far, as a `(min, max)` pair, after each item. The first item seeds 
+    /// both extremes, which are kept in the iterator's data. An item that 
+    /// compares as neither less nor greater, such as a NaN, leaves the 
+    /// extremes unchanged. Leading items that can't be compared with 
+    /// themselves can't seed the extremes, so they're skipped without 
+    /// yielding.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = [2, 1, 3].iter().running_extremes().collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![(&2, &2), (&1, &2), (&1, &3)]);
+    /// ```
+    ///
+    fn running_extremes(self) -> Adapter<(Self, 
+                                          Option<(Self::Item, Self::Item)>), 
+                                         (Self::Item, Self::Item)>
+    //
+    where Self::Item: PartialOrd + Clone,
+    {
+        ParamFromFnIter::new(
+            (self, None),
+            |(iter, extremes)| {
+                loop {
+                    let item = iter.next()?;
+                    match extremes {
+                        Some((min, max)) => {
+                            if item < *min {
+                                *min = item;
+                            } else if item > *max {
+                                *max = item;
+                            }
+                        },
+                        None if item.partial_cmp(&item).is_none() => continue,
+                        None => *extremes = Some((item.clone(), item)),
+                    }
+                    return extremes.clone();
+                }
+            })
+    }
+
+    /// Generalizes `.windows()` to windows of `size` consecutive items that 
+    /// start `stride` items apart. A `stride` below `size` overlaps the 
+    /// windows, one equal to `size` tiles them like `.chunks()`, and one above
+    /// `size` skips the items between them. A partial final window is 
+    /// dropped. The window is kept in a `VecDeque` in the iterator's data, 
+    /// along with the number of items still to skip before the next one.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = (1..=6).chunks_stride(3, 2).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![vec![1, 2, 3], vec![3, 4, 5]]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `size`      - The number of items in each window.
+    /// * `stride`    - The distance between the starts of adjacent windows.
+    ///
+    /// # Panics
+    /// Panics if `size` or `stride` is 0.
+    ///
+    #[cfg(feature = "alloc")]
+    fn chunks_stride(self, 
+                     size   : usize, 
+                     stride : usize
+                    ) -> Adapter<(Self, 
+                                  VecDeque<Self::Item>, 
+                                  usize, 
+                                  usize, 
+                                  usize), 
+                                 Vec<Self::Item>>
+    //
+    where Self::Item: Clone,
+    {
+        assert!(size != 0, "window size must be non-zero");
+        assert!(stride != 0, "stride must be non-zero");
+        ParamFromFnIter::new(
+            (self, VecDeque::with_capacity(size), size, stride, 0),
+            |(iter, window, size, stride, skip)| {
+                while *skip > 0 {
+                    iter.next()?;
+                    *skip -= 1;
+                }
+                while window.len() < *size {
+                    window.push_back(iter.next()?);
+                }
+                let chunk = window.iter().cloned().collect();
+                let popped = (*stride).min(*size);
+                window.drain(..popped);
+                *skip = *stride - popped;
+                Some(chunk)
+            })
+    }
+
+    /// Returns an iterator over non-overlapping chunks of `N` items, each 
+    /// yielded as a `[T; N]` array. Items are gathered in a `Vec` in the 
+    /// iterator's data and moved into an array once `N` have arrived. A 
+    /// partial final chunk isn't yielded; once iteration ends, 
+    /// `.into_remainder()` returns its items.
+    ///
+    /// std has an unstable `Iterator::array_chunks()` of the same name, so 
+    /// the fully qualified syntax avoids the `unstable_name_collisions` lint.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let mut iter = IterMapExt::array_chunks::<2>("abcde".chars());
+    ///
+    /// assert_eq!(iter.by_ref().collect::<Vec<_>>(), 
+    ///            vec![['a', 'b'], ['c', 'd']]);
+    /// assert_eq!(iter.into_remainder(), vec!['e']);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `N` is 0.
+    ///
+    #[cfg(feature = "alloc")]
+    fn array_chunks<const N: usize>(self
+                                   ) -> Adapter<ArrayChunksState<Self, 
+                                                                 Self::Item>, 
+                                                [Self::Item; N]>
+    {
+        assert!(N != 0, "chunk size must be non-zero");
+        ParamFromFnIter::new(
+            ArrayChunksState { iter: self, buffer: Vec::with_capacity(N) },
+            |ArrayChunksState { iter, buffer }| {
+                while buffer.len() < N {
+                    buffer.push(iter.next()?);
+                }
+                let chunk = core::mem::replace(buffer, Vec::with_capacity(N));
+                TryFrom::try_from(chunk).ok()
+            })
+    }
+
+    /// Returns an iterator that pairs each item with its span in the source,
+    /// for reporting positions in tokenizer pipelines. `len_fn` gives the 
+    /// length each item consumed; spans start at offset 0 and follow one 
+    /// another. The end offset so far is kept in the iterator's data.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = ["let", " ", "x"].iter().spanned(|s| s.len())
+    ///                                 .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![(0..3, &"let"), (3..4, &" "), (4..5, &"x")]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `len_fn`    - The closure returning the length of an item.
+    ///
+    fn spanned<G>(self, len_fn: G) -> Adapter<(Self, usize, G), 
+                                              (Range<usize>, Self::Item)>
+    //
+    where G: FnMut(&Self::Item) -> usize,
+    {
+        ParamFromFnIter::new(
+            (self, 0, len_fn),
+            |(iter, offset, len_fn)| {
+                let item = iter.next()?;
+                let start = *offset;
+                *offset += len_fn(&item);
+                Some((start..*offset, item))
+            })
+    }
+
+    /// Returns an iterator that passes the items through unchanged and calls
+    /// `report` with the number of items yielded so far after every `every`
+    /// items, for progress reporting in long pipelines. The count and 
+    /// `report` are kept in the iterator's data.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let mut reports = Vec::new();
+    /// let n = (0..7).report_progress(3, |count| reports.push(count)).count();
+    ///
+    /// assert_eq!(n, 7);
+    /// assert_eq!(reports, vec![3, 6]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `every`     - The number of items between reports.
+    /// * `report`    - The closure passed the running count.
+    ///
+    /// # Panics
+    /// Panics if `every` is 0.
+    ///
+    fn report_progress<G>(self, 
+                          every  : usize, 
+                          report : G
+                         ) -> Adapter<(Self, usize, usize, G), Self::Item>
+    //
+    where G: FnMut(usize),
+    {
+        assert!(every != 0, "report interval must be non-zero");
+        ParamFromFnIter::new(
+            (self, 0, every, report),
+            |(iter, count, every, report)| {
+                let item = iter.next()?;
+                *count += 1;
+                if *count % *every == 0 {
+                    report(*count);
+                }
+                Some(item)
+            })
+    }
+
+    /// Returns an iterator of running sums of integer items that clamp at the
+    /// type's bounds with `saturating_add()` instead of overflowing. The sum
+    /// is kept in the iterator's data.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = [100_u8, 100, 100].iter().copied().running_sum_saturating()
+    ///                           .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![100, 200, 255]);
+    /// ```
+    ///
+    fn running_sum_saturating(self) -> Adapter<(Self, Self::Item), Self::Item>
+    //
+    where Self::Item: OverflowAdd,
+    {
+        ParamFromFnIter::new(
+            (self, Default::default()),
+            |(iter, sum)| {
+                *sum = sum.saturating_add(iter.next()?);
+                Some(*sum)
+            })
+    }
+
+    /// Like `.running_sum_saturating()`, but the sums wrap around at the 
+    /// type's bounds with `wrapping_add()`.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let v = [100_u8, 100, 100].iter().copied().running_sum_wrapping()
+    ///                           .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![100, 200, 44]);
+    /// ```
+    ///
+    fn running_sum_wrapping(self) -> Adapter<(Self, Self::Item), Self::Item>
+    //
+    where Self::Item: OverflowAdd,
+    {
+        ParamFromFnIter::new(
+            (self, Default::default()),
+            |(iter, sum)| {
+                *sum = sum.wrapping_add(iter.next()?);
+                Some(*sum)
+            })
+    }
+
+    /// Returns an iterator over the items before the first one `is_sentinel`
+    /// matches, for polling sources that signal "no data right now" with a 
+    /// sentinel value. Unlike `.take_while()`, the sentinel isn't consumed: 
+    /// the source is kept in the iterator's data as a `Peekable` holding it,
+    /// so `.into_data()` recovers the sentinel and the items after it.
+    ///
+    /// ```
+    /// use iter_map::IterMapExt;
+    ///
+    /// let mut iter = [1, 2, 0, 3].iter().take_until_sentinel(|n| **n == 0);
+    ///
+    /// assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![&1, &2]);
+    ///
+    /// let (rest, _) = iter.into_data();
+    ///
+    /// assert_eq!(rest.collect::<Vec<_>>(), vec![&0, &3]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `is_sentinel` - The predicate matching the sentinel.
+    ///
+    fn take_until_sentinel<G>(self, 
+                              is_sentinel: G
+                             ) -> Adapter<(Peekable<Self>, G), Self::Item>
+    //
+    where G: FnMut(&Self::Item) -> bool,
+    {
+        ParamFromFnIter::new(
+            (self.peekable(), is_sentinel),
+            |(iter, is_sentinel)| iter.next_if(|item| !is_sentinel(item)))
+    }
+}
+
+/// Adds the adapters to all Iterator classes.
+///
+impl<I> IterMapExt for I
+//
+where I: Iterator,
+{
+}
+
+/// Returns the next pair of adjacent items, keeping the second in `prev` so
+/// it becomes the first of the following pair.
+///
+fn next_pair<I, T>(iter: &mut I, prev: &mut Option<T>) -> Option<(T, T)>
+//
+where I: Iterator<Item = T>,
+      T: Clone,
+{
+    let first = match prev.take() {
+        Some(item) => item,
+        None => iter.next()?,
+    };
+    let second = iter.next()?;
+    *prev = Some(second.clone());
+    Some((first, second))
+}
+
+/// Feeds `byte` into the CRC-32 register `crc`, bit by bit using the 
+/// reflected IEEE polynomial.
+///
+fn crc32_update(mut crc: u32, byte: u8) -> u32
+{
+    crc ^= u32::from(byte);
+    for _ in 0..8 {
+        let mask = (crc & 1).wrapping_neg();
+        crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+    }
+    crc
+}
+
+/// Slides `window` forward by one item from `iter`, first filling it up to 
+/// `size` items. Returns `None` if `iter` runs out before the window is full.
+///
+#[cfg(feature = "alloc")]
+fn slide<I, T>(iter   : &mut I, 
+               window : &mut VecDeque<T>, 
+               size   : usize
+              ) -> Option<()>
+//
+where I: Iterator<Item = T>,
+{
+    if window.len() == size {
+        window.pop_front();
+    }
+    while window.len() < size {
+        window.push_back(iter.next()?);
+    }
+    Some(())
+}
+
+/// Pulls the rest of the leading run where `pred` is false into `buffer` and 
+/// marks the split as found.
+///
+#[cfg(feature = "alloc")]
+fn find_split<I, G>(shared: &mut SplitShared<I, G>)
+//
+where I: Iterator,
+      G: FnMut(&I::Item) -> bool,
+{
+    let (iter, pred, buffer, found) = shared;
+    if !*found {
+        while let Some(item) = iter.next_if(|item| !pred(item)) {
+            buffer.push_back(item);
+        }
+        *found = true;
+    }
+}
+
+/// Returns the first half of a `.split_at_predicate()`, yielding the leading
+/// run of items `pred` rejects.
+///
+#[cfg(feature = "alloc")]
+fn split_before<I, G>(shared: Rc<RefCell<SplitShared<I, G>>>) -> SplitHalf<I, G>
+//
+where I: Iterator,
+      G: FnMut(&I::Item) -> bool,
+{
+    ParamFromFnIter::new(
+        shared,
+        |shared| {
+            let (iter, pred, buffer, found) = &mut *shared.borrow_mut();
+            if let Some(item) = buffer.pop_front() {
+                return Some(item);
+            }
+            if *found {
+                return None;
+            }
+            let item = iter.next_if(|item| !pred(item));
+            *found = item.is_none();
+            item
+        })
+}
+
+/// Returns the second half of a `.split_at_predicate()`, yielding the items
+/// from the split point on.
+///
+#[cfg(feature = "alloc")]
+fn split_after<I, G>(shared: Rc<RefCell<SplitShared<I, G>>>) -> SplitHalf<I, G>
+//
+where I: Iterator,
+      G: FnMut(&I::Item) -> bool,
+{
+    ParamFromFnIter::new(
+        shared,
+        |shared| {
+            let shared = &mut *shared.borrow_mut();
+            find_split(shared);
+            shared.0.next()
+        })
+}
+
+/// Returns the half of a `.tee()` for `side`, which pops from its own 
+/// buffer and otherwise pulls from the source, buffering a clone for the 
+/// other side while it's still alive.
+///
+#[cfg(feature = "alloc")]
+fn tee_side<I>(side: SharedSide<TeeShared<I>>) -> Tee<I>
+//
+where I: Iterator,
+      I::Item: Clone,
+{
+    ParamFromFnIter::new(
+        side,
+        |SharedSide { shared, side }| {
+            let ((iter, buffers), alive) = &mut *shared.borrow_mut();
+            if let Some(item) = buffers[*side].pop_front() {
+                return Some(item);
+            }
+            let item = iter.next()?;
+            if alive[1 - *side] {
+                buffers[1 - *side].push_back(item.clone());
+            }
+            Some(item)
+        })
+}
+
+impl<I, S, G, R> Adapter<ScanState<I, S, G>, R>
+{
+    /// Consumes the iterator and returns the state of an adapter whose data
+    /// is a `ScanState`, such as the one returned by `IterMapExt::scan_map()`.
+    ///
+    pub fn into_state(self) -> S
+    {
+        self.into_data().state
+    }
+}
+
+impl<I, G, R> Adapter<ExtractState<I, G>, R>
+{
+    /// Consumes the iterator and returns the number of items the adapter 
+    /// returned by `IterMapExt::extract_map()` pulled from its source.
+    ///
+    pub fn into_attempts(self) -> usize
+    {
+        self.into_data().attempts
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<I, T, R> Adapter<ArrayChunksState<I, T>, R>
+{
+    /// Consumes the iterator and returns the items of the trailing partial 
+    /// chunk of `IterMapExt::array_chunks()`. Before iteration has ended, 
+    /// these are the items gathered toward the next chunk.
+    ///
+    pub fn into_remainder(self) -> Vec<T>
+    {
+        self.into_data().buffer
+    }
+}
+
+/// Returns the left half of an `.unzip_map()`, which pops from its own 
+/// buffer and otherwise pulls a pair from the source, buffering the right 
+/// half while the other side is still alive.
+///
+#[cfg(feature = "alloc")]
+fn unzip_lefts<I, A, B>(side: SharedSide<UnzipShared<I, A, B>>
+                       ) -> UnzipLefts<I, A, B>
+//
+where I: Iterator<Item = (A, B)>,
+{
+    ParamFromFnIter::new(
+        side,
+        |SharedSide { shared, .. }| {
+            let ((iter, lefts, rights), alive) = &mut *shared.borrow_mut();
+            if let Some(a) = lefts.pop_front() {
+                return Some(a);
+            }
+            let (a, b) = iter.next()?;
+            if alive[1] {
+                rights.push_back(b);
+            }
+            Some(a)
+        })
+}
+
+/// Returns the right half of an `.unzip_map()`, the mirror image of 
+/// `unzip_lefts()`.
+///
+#[cfg(feature = "alloc")]
+fn unzip_rights<I, A, B>(side: SharedSide<UnzipShared<I, A, B>>
+                        ) -> UnzipRights<I, A, B>
+//
+where I: Iterator<Item = (A, B)>,
+{
+    ParamFromFnIter::new(
+        side,
+        |SharedSide { shared, .. }| {
+            let ((iter, lefts, rights), alive) = &mut *shared.borrow_mut();
+            if let Some(b) = rights.pop_front() {
+                return Some(b);
+            }
+            let (a, b) = iter.next()?;
+            if alive[0] {
+                lefts.push_back(a);
+            }
+            Some(b)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn chunks_exact_multiple() {
+        let v = (1..=6).chunks(3).collect::<Vec<_>>();
+        assert_eq!(v, vec![vec![1, 2, 3], vec![4, 5, 6]]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn chunks_ragged_final_chunk() {
+        let v = (1..=7).chunks(3).collect::<Vec<_>>();
+        assert_eq!(v, vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn chunks_empty_input() {
+        assert_eq!((0..0).chunks(3).count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic(expected = "chunk size must be non-zero")]
+    fn chunks_zero_size() {
+        let _ = (0..3).chunks(0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn windows_match_slice_windows() {
+        let data = [1, 2, 3, 4, 5];
+        for size in 1..=5 {
+            let v = data.iter().copied().windows(size).collect::<Vec<_>>();
+            let expected = data.windows(size)
+                               .map(|w| w.to_vec())
+                               .collect::<Vec<_>>();
+            assert_eq!(v, expected);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn windows_short_input() {
+        assert_eq!((1..3).windows(3).count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic(expected = "window size must be non-zero")]
+    fn windows_zero_size() {
+        let _ = (0..3).windows(0);
+    }
+
+    #[test]
+    fn intersperse_empty() {
+        assert_eq!(IterMapExt::intersperse(0..0, 9).count(), 0);
+    }
+
+    #[test]
+    fn intersperse_single() {
+        let v = IterMapExt::intersperse(1..2, 0).collect::<Vec<_>>();
+        assert_eq!(v, vec![1]);
+    }
+
+    #[test]
+    fn intersperse_many() {
+        let v = IterMapExt::intersperse(1..4, 0).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 0, 2, 0, 3]);
+        assert_eq!(v.last(), Some(&3));
+    }
+
+    #[test]
+    fn intersperse_with_call_counts() {
+        for n in 0..5_usize {
+            let mut calls = 0;
+            let v = IterMapExt::intersperse_with(0..n, || { calls += 1; 99 })
+                        .collect::<Vec<_>>();
+            assert_eq!(v.len(), (2 * n).saturating_sub(1));
+            assert_eq!(calls, n.saturating_sub(1));
+        }
+    }
+
+    #[test]
+    fn dedup_runs() {
+        let v = [1, 1, 2, 3, 3, 2, 4, 4].iter().dedup().collect::<Vec<_>>();
+        assert_eq!(v, vec![&1, &2, &3, &2, &4]);
+    }
+
+    #[test]
+    fn dedup_all_equal() {
+        let v = [7; 5].iter().dedup().collect::<Vec<_>>();
+        assert_eq!(v, vec![&7]);
+        assert_eq!((0..0).dedup().count(), 0);
+    }
+
+    #[test]
+    fn dedup_by_custom_eq() {
+        let v = [1, 3, 2, 4, 5, 8].iter()
+                                  .dedup_by(|a, b| *a % 2 == *b % 2)
+                                  .collect::<Vec<_>>();
+        assert_eq!(v, vec![&1, &2, &5, &8]);
+    }
+
+    #[test]
+    fn run_length_encode_runs() {
+        let v = ['a', 'a', 'b', 'c', 'c', 'c'].iter()
+                                              .run_length_encode()
+                                              .collect::<Vec<_>>();
+        assert_eq!(v, vec![(2, &'a'), (1, &'b'), (3, &'c')]);
+    }
+
+    #[test]
+    fn run_length_encode_empty() {
+        assert_eq!((0..0).run_length_encode().count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn group_consecutive_by_parity() {
+        let v = [2, 4, 1, 3, 5, 6].iter()
+                                  .copied()
+                                  .group_consecutive_by(|n| n % 2 == 0)
+                                  .collect::<Vec<_>>();
+        assert_eq!(v, vec![(true, vec![2, 4]), 
+                           (false, vec![1, 3, 5]), 
+                           (true, vec![6])]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn group_consecutive_by_non_adjacent_keys() {
+        let v = "aabaa".chars()
+                       .group_consecutive_by(|&ch| ch)
+                       .map(|(key, group)| (key, group.len()))
+                       .collect::<Vec<_>>();
+        assert_eq!(v, vec![('a', 2), ('b', 1), ('a', 2)]);
+        assert_eq!((0..0).group_consecutive_by(|&n| n).count(), 0);
+    }
+
+    #[test]
+    fn scan_map_running_sum() {
+        let mut iter = [1, 2, 3, 4].iter().scan_map(0, |sum, &n| {
+            *sum += n;
+            Some(*sum)
+        });
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![1, 3, 6, 10]);
+        assert_eq!(iter.into_state(), 10);
+    }
+
+    #[test]
+    fn scan_map_stops_on_none() {
+        let mut iter = (1..).scan_map(0, |sum, n| {
+            *sum += n;
+            if *sum > 10 { None } else { Some(n) }
+        });
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(iter.into_state(), 15);
+    }
+
+    #[test]
+    fn accumulate_running_sum() {
+        let v = [1, 2, 3].iter().accumulate(0, |a, n| a + *n)
+                                .collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 3, 6]);
+    }
+
+    #[test]
+    fn accumulate_running_max() {
+        let v = [3, 1, 4, 1, 5].iter().accumulate(i32::MIN, |a, &&n| {
+            (*a).max(n)
+        }).collect::<Vec<_>>();
+        assert_eq!(v, vec![3, 3, 4, 4, 5]);
+    }
+
+    #[test]
+    fn accumulate_empty() {
+        assert_eq!((0..0).accumulate(0, |a, n| a + n).count(), 0);
+        let v = (0..0).accumulate_from(7, |a, n| a + n).collect::<Vec<_>>();
+        assert_eq!(v, vec![7]);
+    }
+
+    #[test]
+    fn pairwise_deltas() {
+        let v = [10, 13, 9].iter().copied().pairwise().collect::<Vec<_>>();
+        assert_eq!(v, vec![(10, 13), (13, 9)]);
+        let deltas = v.iter().map(|(a, b)| b - a).collect::<Vec<_>>();
+        assert_eq!(deltas, vec![3, -4]);
+    }
+
+    #[test]
+    fn pairwise_short_input() {
+        assert_eq!((0..1).pairwise().count(), 0);
+        assert_eq!((0..0).pairwise().count(), 0);
+    }
+
+    #[test]
+    fn with_position_lengths() {
+        use Position::*;
+
+        let positions = |n| (0..n).with_position()
+                                  .map(|(p, _)| p)
+                                  .collect::<Vec<_>>();
+        assert_eq!(positions(0), vec![]);
+        assert_eq!(positions(1), vec![Only]);
+        assert_eq!(positions(2), vec![First, Last]);
+        assert_eq!(positions(3), vec![First, Middle, Last]);
+    }
+
+    #[test]
+    fn pad_with_under_length() {
+        let v = (1..=2).pad_with(5, |i| i * 10).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 20, 30, 40]);
+    }
+
+    #[test]
+    fn pad_with_exact_and_over_length() {
+        let v = (1..=3).pad_with(3, |_| 0).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3]);
+        let v = (1..=4).pad_with(2, |_| 0).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn take_until_inclusive_middle() {
+        let mut iter = (1..=6).take_until_inclusive(|&n| n == 3);
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(iter.into_data().0.next(), Some(4));
+    }
+
+    #[test]
+    fn take_until_inclusive_first_and_never() {
+        let v = (1..=3).take_until_inclusive(|_| true).collect::<Vec<_>>();
+        assert_eq!(v, vec![1]);
+        let v = (1..=3).take_until_inclusive(|_| false).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn interleave_equal_lengths() {
+        let v = (1..=3).interleave(4..=6).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 4, 2, 5, 3, 6]);
+    }
+
+    #[test]
+    fn interleave_unequal_lengths() {
+        let v = (1..=2).interleave(4..=7).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 4, 2, 5, 6, 7]);
+        let v = (1..=4).interleave(7..=7).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 7, 2, 3, 4]);
+    }
+
+    #[test]
+    fn interleave_one_empty() {
+        let v = (0..0).interleave(1..=3).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3]);
+        let v = (1..=3).interleave(0..0).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn zip_longest_left_and_right_longer() {
+        use Pair::*;
+
+        let v = (1..=3).zip_longest(4..=4).collect::<Vec<_>>();
+        assert_eq!(v, vec![Both(1, 4), Left(2), Left(3)]);
+        let v = (1..=1).zip_longest(4..=6).collect::<Vec<_>>();
+        assert_eq!(v, vec![Both(1, 4), Right(5), Right(6)]);
+    }
+
+    #[test]
+    fn zip_longest_equal_and_empty() {
+        use Pair::*;
+
+        let v = (1..=2).zip_longest("ab".chars()).collect::<Vec<_>>();
+        assert_eq!(v, vec![Both(1, 'a'), Both(2, 'b')]);
+        assert_eq!((0..0).zip_longest(0..0).count(), 0);
+    }
+
+    #[test]
+    fn step_fn_constant_stride() {
+        let v = (0..20).step_fn(|_| 2).collect::<Vec<_>>();
+        let expected = (0..20).step_by(3).collect::<Vec<_>>();
+        assert_eq!(v, expected);
+    }
+
+    #[test]
+    fn step_fn_data_dependent_stride() {
+        let data = [1, 5, 0, 0, 2, 9, 9, 4, 8];
+        let v = data.iter().step_fn(|&&n| n.min(2)).collect::<Vec<_>>();
+        assert_eq!(v, vec![&1, &0, &0, &2, &4]);
+    }
+
+    #[test]
+    fn step_fn_past_the_end() {
+        let v = (0..5).step_fn(|_| 100).collect::<Vec<_>>();
+        assert_eq!(v, vec![0]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn moving_average_matches_manual() {
+        let data = [1.0, 2.0, 3.0, 4.0];
+        let v = data.iter().copied().moving_average(2).collect::<Vec<_>>();
+        let expected = data.windows(2)
+                           .map(|w| (w[0] + w[1]) / 2.0)
+                           .collect::<Vec<_>>();
+        assert_eq!(v, expected);
+        assert_eq!(v, vec![1.5, 2.5, 3.5]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn moving_average_short_input() {
+        assert_eq!([1.0_f32].iter().copied().moving_average(2).count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic(expected = "window size must be non-zero")]
+    fn moving_average_zero_window() {
+        let _ = (0..3_u8).moving_average(0);
+    }
+
+    #[test]
+    fn differences_of_squares() {
+        let v = [1, 4, 9, 16].iter().copied().differences().collect::<Vec<_>>();
+        assert_eq!(v, vec![3, 5, 7]);
+        let v = [1.5, 1.0].iter().copied().differences().collect::<Vec<_>>();
+        assert_eq!(v, vec![-0.5]);
+    }
+
+    #[test]
+    fn differences_short_input() {
+        assert_eq!((0..0).differences().count(), 0);
+        assert_eq!((0..1).differences().count(), 0);
+    }
+
+    #[test]
+    fn enumerate_from_one() {
+        let v = "xyz".chars().enumerate_from(1).collect::<Vec<_>>();
+        assert_eq!(v, vec![(1, 'x'), (2, 'y'), (3, 'z')]);
+    }
+
+    #[test]
+    fn enumerate_from_near_max() {
+        let v = (0..2).enumerate_from(usize::MAX - 1)
+                      .map(|(i, _)| i)
+                      .collect::<Vec<_>>();
+        assert_eq!(v, vec![usize::MAX - 1, usize::MAX]);
+    }
+
+    #[test]
+    #[should_panic(expected = "enumerate_from index overflowed")]
+    fn enumerate_from_overflow() {
+        (0..3).enumerate_from(usize::MAX).for_each(drop);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn cycle_n_repeats() {
+        assert_eq!((1..=3).cycle_n(0).count(), 0);
+        assert_eq!((1..=3).cycle_n(1).collect::<Vec<_>>(), vec![1, 2, 3]);
+        let v = (1..=3).cycle_n(3).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3, 1, 2, 3, 1, 2, 3]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn cycle_n_empty_source() {
+        for times in 0..4 {
+            let mut iter = (0..0).cycle_n(times);
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.next(), None);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn cycle_n_single_pass_does_not_buffer() {
+        let mut iter = (1..=3).cycle_n(1);
+        iter.by_ref().for_each(drop);
+        assert!(iter.data().1.is_empty());
+    }
+
+    #[test]
+    fn repeat_each_twice() {
+        let v = ['a', 'b'].iter().repeat_each(2).collect::<Vec<_>>();
+        assert_eq!(v, vec![&'a', &'a', &'b', &'b']);
+    }
+
+    #[test]
+    fn repeat_each_zero_and_one() {
+        assert_eq!(['a', 'b'].iter().repeat_each(0).count(), 0);
+        assert_eq!((1..=3).repeat_each(1).collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn flatten_options_interleaved() {
+        let v = [Some(1), None, None, Some(2), None, Some(3)].iter()
+                                                          .copied()
+                                                          .flatten_options()
+                                                          .collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn flatten_options_all_none() {
+        let v = [None::<i32>, None].iter().copied().flatten_options().count();
+        assert_eq!(v, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn split_by_trailing_delimiter() {
+        let v = [1, 0, 2, 3, 0].iter()
+                               .copied()
+                               .split_by(|n| *n == 0)
+                               .collect::<Vec<_>>();
+        assert_eq!(v, vec![vec![1], vec![2, 3], vec![]]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn split_by_edges() {
+        let v = [0, 1].iter().split_by(|n| **n == 0).collect::<Vec<_>>();
+        assert_eq!(v, vec![vec![], vec![&1]]);
+        let v = [1, 2].iter().split_by(|n| **n == 0).collect::<Vec<_>>();
+        assert_eq!(v, vec![vec![&1, &2]]);
+        assert_eq!((0..0).split_by(|n| *n == 0).count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn batch_by_key_monotonic_keys() {
+        let v = (0..7).batch_by_key(|n| n / 3).collect::<Vec<_>>();
+        assert_eq!(v, vec![(0, vec![0, 1, 2]), 
+                           (1, vec![3, 4, 5]), 
+                           (2, vec![6])]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn batch_by_key_same_key() {
+        let v = (0..4).batch_by_key(|_| 'k').collect::<Vec<_>>();
+        assert_eq!(v, vec![('k', vec![0, 1, 2, 3])]);
+        assert_eq!((0..0).batch_by_key(|_| 'k').count(), 0);
+    }
+
+    #[test]
+    fn cap_stops_calling_callback() {
+        use crate::IntoIterMap;
+        use core::cell::Cell;
+        let calls = Cell::new(0);
+        let mut iter = (1..).iter_map(|it| { calls.set(calls.get() + 1); 
+                                             it.next() })
+                            .cap(2);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn cap_longer_than_input_and_zero() {
+        assert_eq!((0..3).cap(10).count(), 3);
+        assert_eq!((0..3).cap(0).count(), 0);
+    }
+
+    #[test]
+    fn collect_chunks_into_string_and_vec() {
+        let v = "abcdefg".chars().collect_chunks_into::<String>(3)
+                                 .collect::<Vec<_>>();
+        assert_eq!(v, vec!["abc", "def", "g"]);
+        let v = (1..=4).collect_chunks_into::<Vec<_>>(2).collect::<Vec<_>>();
+        assert_eq!(v, vec![vec![1, 2], vec![3, 4]]);
+    }
+
+    #[test]
+    fn collect_chunks_into_hash_set() {
+        use std::collections::HashSet;
+        let v = [1, 1, 2, 3, 3, 3].iter()
+                                  .copied()
+                                  .collect_chunks_into::<HashSet<_>>(3)
+                                  .collect::<Vec<_>>();
+        assert_eq!(v, vec![[1, 2].iter().copied().collect(), 
+                           [3].iter().copied().collect()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be non-zero")]
+    fn collect_chunks_into_zero_size() {
+        let _ = (0..3).collect_chunks_into::<Vec<_>>(0);
+    }
+
+    #[test]
+    fn inspect_mut_doubles_in_order() {
+        let v = [3, 1, 2].iter()
+                         .copied()
+                         .inspect_mut(|n| *n *= 2)
+                         .collect::<Vec<_>>();
+        assert_eq!(v, vec![6, 2, 4]);
+    }
+
+    #[test]
+    fn inspect_mut_call_count() {
+        let mut calls = 0;
+        assert_eq!((0..0).inspect_mut(|_| calls += 1).count(), 0);
+        assert_eq!((0..3).inspect_mut(|_| calls += 1).count(), 3);
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn filter_with_state_strictly_increasing() {
+        let mut iter = [3, 1, 4, 1, 5, 9, 2, 6].iter()
+                                              .copied()
+                                              .filter_with_state(0, |max, n| {
+                                                  let keep = *n > *max;
+                                                  if keep { *max = *n; }
+                                                  keep
+                                              });
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![3, 4, 5, 9]);
+        assert_eq!(iter.into_state(), 9);
+    }
+
+    #[test]
+    fn filter_with_state_rejects_all() {
+        let v = (0..5).filter_with_state((), |_, _| false).count();
+        assert_eq!(v, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn sliding_reduce_sums_and_maxima() {
+        let data = [1, 3, 2, 5, 4];
+        let sums = data.iter()
+                       .copied()
+                       .sliding_reduce(3, |w| w.iter().sum::<i32>())
+                       .collect::<Vec<_>>();
+        assert_eq!(sums, vec![6, 10, 11]);
+        let maxima = data.iter()
+                         .sliding_reduce(2, |w| **w.iter().max().unwrap())
+                         .collect::<Vec<_>>();
+        assert_eq!(maxima, vec![3, 3, 5, 5]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn sliding_reduce_short_input() {
+        assert_eq!((0..2).sliding_reduce(3, |w| w.len()).count(), 0);
+        assert_eq!((0..3).sliding_reduce(3, |w| w.len()).count(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic(expected = "window size must be non-zero")]
+    fn sliding_reduce_zero_window() {
+        let _ = (0..3).sliding_reduce(0, |w| w.len());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn index_by_later_items_win() {
+        let map = [(1, 'a'), (2, 'b'), (1, 'c')].iter()
+                                                .copied()
+                                                .index_by(|p| p.0);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&1], (1, 'c'));
+        assert_eq!(map[&2], (2, 'b'));
+        assert!((0..0).index_by(|n| *n).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn group_into_map_collisions_keep_order() {
+        let map = "abacab".chars().group_into_map(|c| *c);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map[&'a'], vec!['a', 'a', 'a']);
+        assert_eq!(map[&'b'], vec!['b', 'b']);
+        let map = (0..5).group_into_map(|n| n % 2 == 0);
+        assert_eq!(map[&true], vec![0, 2, 4]);
+        assert_eq!(map[&false], vec![1, 3]);
+    }
+
+    #[test]
+    fn retry_flaky_source() {
+        use crate::IntoIterMap;
+        let flaky = || {
+            let mut failures = 0;
+            (1..=2).iter_map(move |iter| {
+                if failures < 2 {
+                    failures += 1;
+                    Some(Err(failures))
+                } else {
+                    iter.next().map(Ok)
+                }
+            })
+        };
+        let v = flaky().retry(2, |_| true).collect::<Vec<_>>();
+        assert_eq!(v, vec![Ok(1), Ok(2)]);
+        let v = flaky().retry(1, |_| true).collect::<Vec<_>>();
+        assert_eq!(v, vec![Err(2), Ok(1), Ok(2)]);
+    }
+
+    #[test]
+    fn retry_rejected_and_trailing_errors() {
+        let v = vec![Err(1), Ok('a'), Err(2)];
+        let r = v.clone().into_iter().retry(5, |_| false).collect::<Vec<_>>();
+        assert_eq!(r, v);
+        let r = v.into_iter().retry(5, |_| true).collect::<Vec<_>>();
+        assert_eq!(r, vec![Ok('a')]);
+    }
+
+    #[test]
+    fn coalesce_same_sign_sums() {
+        let v = [1, 2, -1, -3, 4, -2, -2].iter()
+                                          .copied()
+                                          .coalesce(|a, b| {
+                                              if (a < 0) == (b < 0) {
+                                                  Ok(a + b)
+                                              } else {
+                                                  Err((a, b))
+                                              }
+                                          })
+                                          .collect::<Vec<_>>();
+        assert_eq!(v, vec![3, -4, 4, -4]);
+    }
+
+    #[test]
+    fn coalesce_flushes_held_item() {
+        let v = [1, 2, 3].iter()
+                         .copied()
+                         .coalesce(|a, b| Err((a, b)))
+                         .collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3]);
+        let v = (1..=4).coalesce(|a, b| Ok(a * b)).collect::<Vec<_>>();
+        assert_eq!(v, vec![24]);
+        assert_eq!((0..0).coalesce(|a, b| Ok(a + b)).count(), 0);
+    }
+
+    #[test]
+    fn first_and_rest_yields_tail() {
+        let (first, rest) = (1..=4).first_and_rest().unwrap();
+        assert_eq!(first, 1);
+        assert_eq!(rest.size_hint(), (3, Some(3)));
+        assert_eq!(rest.collect::<Vec<_>>(), vec![2, 3, 4]);
+        let (first, mut rest) = (1..2).first_and_rest().unwrap();
+        assert_eq!(first, 1);
+        assert_eq!(rest.next(), None);
+    }
+
+    #[test]
+    fn first_and_rest_empty() {
+        assert!((0..0).first_and_rest().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn unzip_map_lefts_then_rights() {
+        let (mut lefts, rights) = (1..=3).unzip_map(|n| (n, n * 10));
+        assert_eq!(lefts.by_ref().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!((rights.data().shared.borrow().0).2.len(), 3);
+        assert_eq!(rights.collect::<Vec<_>>(), vec![10, 20, 30]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn unzip_map_interleaved() {
+        let (mut lefts, mut rights) = "abcd".chars()
+                                            .unzip_map(|c| {
+                                                (c, c.to_ascii_uppercase())
+                                            });
+        assert_eq!(rights.next(), Some('A'));
+        assert_eq!(lefts.next(), Some('a'));
+        assert_eq!(lefts.next(), Some('b'));
+        assert_eq!(lefts.next(), Some('c'));
+        assert_eq!(rights.next(), Some('B'));
+        assert_eq!(rights.collect::<String>(), "CD");
+        assert_eq!(lefts.collect::<String>(), "d");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn unzip_map_stops_buffering_after_drop() {
+        let (mut lefts, rights) = (1..=3).unzip_map(|n| (n, n * 10));
+        std::mem::drop(rights);
+        assert_eq!(lefts.by_ref().count(), 3);
+        let ((_, _, rights), alive) = &*lefts.data().shared.borrow();
+        assert!(rights.is_empty());
+        assert_eq!(*alive, [true, false]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn chunks_by_weight_mixed() {
+        let v = [3, 1, 2, 4, 1, 1].iter()
+                                  .copied()
+                                  .chunks_by_weight(5, |n| *n)
+                                  .collect::<Vec<_>>();
+        assert_eq!(v, vec![vec![3, 1], vec![2], vec![4, 1], vec![1]]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn chunks_by_weight_oversized() {
+        let mut calls = 0;
+        let v = [1, 9, 2].iter()
+                         .copied()
+                         .chunks_by_weight(3, |n| { calls += 1; *n })
+                         .collect::<Vec<_>>();
+        assert_eq!(v, vec![vec![1], vec![9], vec![2]]);
+        assert_eq!(calls, 3);
+        assert_eq!((0..0).chunks_by_weight(3, |n| *n).count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn array_windows_of_three() {
+        let v = [1, 2, 3, 4].iter()
+                            .copied()
+                            .array_windows::<3>()
+                            .collect::<Vec<_>>();
+        assert_eq!(v, vec![[1, 2, 3], [2, 3, 4]]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn array_windows_short_input() {
+        assert_eq!((1..3).array_windows::<3>().count(), 0);
+        assert_eq!((1..2).array_windows::<1>().collect::<Vec<_>>(), vec![[1]]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic(expected = "window size must be non-zero")]
+    fn array_windows_zero_size() {
+        let _ = (0..3).array_windows::<0>();
+    }
+
+    #[test]
+    fn try_convert_in_and_out_of_range() {
+        let v = [0_i64, 255, 256, -1, 7].iter()
+                                        .copied()
+                                        .try_convert::<u8>()
+                                        .map(|r| r.ok())
+                                        .collect::<Vec<_>>();
+        assert_eq!(v, vec![Some(0), Some(255), None, None, Some(7)]);
+    }
+
+    #[test]
+    fn try_convert_or_stop_yields_error_once() {
+        let mut pulled = 0;
+        let v = [5_i64, 256, 6].iter()
+                               .inspect(|_| pulled += 1)
+                               .copied()
+                               .try_convert_or_stop::<u8>()
+                               .collect::<Vec<_>>();
+        assert_eq!(v.len(), 2);
+        assert_eq!(v[0], Ok(5));
+        assert!(v[1].is_err());
+        assert_eq!(pulled, 2);
+        let v = (1_i64..=3).try_convert_or_stop::<u8>()
+                           .collect::<Result<Vec<_>, _>>();
+        assert_eq!(v, Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn chunked_fold_sum_then_multiply() {
+        let v = (1..=7).chunked_fold(3, || 0, |s, n| s + n, |a, b| a * b);
+        assert_eq!(v, Some(6 * 15 * 7));
+        let v = (1..=4).chunked_fold(10, || 0, |s, n| s + n, |a, b| a * b);
+        assert_eq!(v, Some(10));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn chunked_fold_empty() {
+        let v = (0..0).chunked_fold(2, || 0, |s, n| s + n, |a, b| a * b);
+        assert_eq!(v, None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn distinct_first_seen_order() {
+        let v = [1, 2, 1, 3, 2, 4].iter()
+                                  .copied()
+                                  .distinct()
+                                  .collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn distinct_all_repeats() {
+        let mut iter = [7, 7, 7].iter().distinct();
+        assert_eq!(iter.next(), Some(&7));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.data().1.len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn distinct_by_id_field() {
+        #[derive(Debug, PartialEq)]
+        struct Record { id: u32, name: &'static str }
+        let records = vec![Record { id: 1, name: "a" }, 
+                           Record { id: 2, name: "b" }, 
+                           Record { id: 1, name: "c" }, 
+                           Record { id: 3, name: "d" }, 
+                           Record { id: 2, name: "e" }];
+        let names = records.into_iter()
+                           .distinct_by(|r| r.id)
+                           .map(|r| r.name)
+                           .collect::<Vec<_>>();
+        assert_eq!(names, vec!["a", "b", "d"]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn distinct_by_constant_key() {
+        assert_eq!((0..5).distinct_by(|_| ()).collect::<Vec<_>>(), vec![0]);
+        assert_eq!((0..0).distinct_by(|_| ()).count(), 0);
+    }
+
+    #[test]
+    fn positions_of_evens() {
+        let v = [1, 2, 3, 4].iter().positions(|n| *n % 2 == 0)
+                            .collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 3]);
+    }
+
+    #[test]
+    fn positions_none_and_all() {
+        assert_eq!((0..4).positions(|_| false).count(), 0);
+        let v = (5..8).positions(|_| true).collect::<Vec<_>>();
+        assert_eq!(v, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn extract_map_parsed_integers() {
+        let words = ["4", "x", "-2", "", "10", "y"];
+        let mut iter = words.iter().extract_map(|s| s.parse::<i32>().ok());
+        assert_eq!(iter.next(), Some(4));
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![-2, 10]);
+        assert_eq!(iter.into_attempts(), 6);
+    }
+
+    #[test]
+    fn extract_map_partial_consumption() {
+        let mut iter = (1..=10).extract_map(|n| if n % 4 == 0 { Some(n) } 
+                                                else { None });
+        assert_eq!(iter.next(), Some(4));
+        assert_eq!(iter.into_attempts(), 4);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn tee_drain_one_then_other() {
+        let (a, b) = "abc".chars().tee();
+        assert_eq!(a.collect::<String>(), "abc");
+        assert_eq!(b.collect::<String>(), "abc");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn tee_interleaved() {
+        let (mut a, mut b) = (1..=4).tee();
+        assert_eq!(a.next(), Some(1));
+        assert_eq!(a.next(), Some(2));
+        assert_eq!(b.next(), Some(1));
+        assert_eq!(b.next(), Some(2));
+        assert_eq!(b.next(), Some(3));
+        assert_eq!((a.data().shared.borrow().0).1[0].len(), 1);
+        assert_eq!(a.collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(b.collect::<Vec<_>>(), vec![4]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn tee_stops_buffering_after_drop() {
+        let (mut a, b) = (1..=3).tee();
+        std::mem::drop(b);
+        assert_eq!(a.by_ref().count(), 3);
+        let ((_, buffers), alive) = &*a.data().shared.borrow();
+        assert!(buffers.iter().all(|buffer| buffer.is_empty()));
+        assert_eq!(*alive, [true, false]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn tee_stops_buffering_after_handle_drop() {
+        let (mut a, b) = (1..=3).tee();
+        let (_, side) = b.into_parts();
+        assert_eq!(a.next(), Some(1));
+        std::mem::drop(side);
+        assert_eq!(a.by_ref().count(), 2);
+        assert_eq!((a.data().shared.borrow().0).1[1].len(), 1);
+    }
+
+    #[test]
+    fn on_exhausted_runs_once_at_the_end() {
+        use core::cell::Cell;
+        let calls = Cell::new(0);
+        let mut iter = (0..2).on_exhausted(|| calls.set(calls.get() + 1));
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(calls.get(), 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(iter.next(), None);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn on_exhausted_not_run_on_early_drop() {
+        use core::cell::Cell;
+        let calls = Cell::new(0);
+        let v = (0..5).on_exhausted(|| calls.set(calls.get() + 1))
+                      .take(2)
+                      .collect::<Vec<_>>();
+        assert_eq!(v, vec![0, 1]);
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn on_drop_runs_once_on_drop() {
+        use core::cell::Cell;
+        let calls = Cell::new(0);
+        let mut iter = (0..2).on_drop(|| calls.set(calls.get() + 1));
+        assert_eq!(iter.by_ref().count(), 2);
+        assert_eq!(calls.get(), 0);
+        core::mem::drop(iter);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn every_nth_two() {
+        let v = (0..10).every_nth(2).collect::<Vec<_>>();
+        assert_eq!(v, vec![0, 2, 4, 6, 8]);
+        let expected = (0..10).step_by(3).collect::<Vec<_>>();
+        assert_eq!((0..10).every_nth(3).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn every_nth_pulls_every_item() {
+        use crate::IntoIterMap;
+        let mut calls = 0;
+        let v = (0..5).iter_map(|it| { calls += 1; it.next() })
+                      .every_nth(4)
+                      .collect::<Vec<_>>();
+        assert_eq!(v, vec![0, 4]);
+        assert_eq!(calls, 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "step must be non-zero")]
+    fn every_nth_zero() {
+        let _ = (0..3).every_nth(0);
+    }
+
+    #[test]
+    fn merge_sorted_interleaving_inputs() {
+        let v = [1, 3, 5].iter()
+                         .copied()
+                         .merge_sorted(vec![2, 4, 6])
+                         .collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3, 4, 5, 6]);
+        let v = (0..0).merge_sorted(1..3).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2]);
+        let v = (5..7).merge_sorted(0..2).collect::<Vec<_>>();
+        assert_eq!(v, vec![0, 1, 5, 6]);
+    }
+
+    #[test]
+    fn merge_sorted_by_is_stable() {
+        let left = [(1, 'a'), (2, 'a')];
+        let right = [(1, 'b'), (2, 'b'), (3, 'b')];
+        let v = left.iter()
+                    .merge_sorted_by(right.iter(), |x, y| x.0.cmp(&y.0))
+                    .map(|p| p.1)
+                    .collect::<String>();
+        assert_eq!(&v, "ababb");
+    }
+
+    #[test]
+    fn map_if_capitalizes_vowels() {
+        let s = "iterator map".chars()
+                              .map_if(|c| "aeiou".contains(*c), 
+                                      |c| c.to_ascii_uppercase())
+                              .collect::<String>();
+        assert_eq!(&s, "ItErAtOr mAp");
+    }
+
+    #[test]
+    fn map_if_never_matches() {
+        let v = (0..3).map_if(|_| false, |_| unreachable!())
+                      .collect::<Vec<_>>();
+        assert_eq!(v, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn running_checksum_known_values() {
+        let fox = b"The quick brown fox jumps over the lazy dog";
+        let crc = fox.iter().copied().running_checksum().last();
+        assert_eq!(crc, Some(0x414F_A339));
+        assert_eq!(b"".iter().copied().running_checksum().last(), None);
+    }
+
+    #[test]
+    fn running_checksum_intermediate_values() {
+        let v = b"abc".iter().copied().running_checksum().collect::<Vec<_>>();
+        assert_eq!(v, vec![0xE8B7_BE43, 0x9E83_486D, 0x3524_41C2]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn cached_map_hits_within_window() {
+        let mut seen = Vec::new();
+        let v = [1, 2, 1, 3, 2, 1].iter()
+                                  .copied()
+                                  .cached_map(3, |n| {
+                                      assert!(!seen.contains(n), 
+                                              "recomputed {}", n);
+                                      seen.push(*n);
+                                      n * 10
+                                  })
+                                  .collect::<Vec<_>>();
+        assert_eq!(v, vec![10, 20, 10, 30, 20, 10]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn cached_map_evicts_least_recent() {
+        let mut calls = Vec::new();
+        let v = [1, 2, 1, 3, 2, 1].iter()
+                                  .copied()
+                                  .cached_map(2, |n| { calls.push(*n); -n })
+                                  .collect::<Vec<_>>();
+        assert_eq!(v, vec![-1, -2, -1, -3, -2, -1]);
+        assert_eq!(calls, vec![1, 2, 3, 2, 1]);
+        let mut calls = 0;
+        assert_eq!([5, 5].iter().cached_map(0, |_| calls += 1).count(), 2);
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn peek_map_direction_markers() {
+        let v = [1, 3, 2, 2].iter()
+                            .peek_map(|n, next| match next {
+                                Some(m) if m > n => "ascending",
+                                Some(m) if m < n => "descending",
+                                Some(_) => "flat",
+                                None => "end",
+                            })
+                            .collect::<Vec<_>>();
+        assert_eq!(v, vec!["ascending", "descending", "flat", "end"]);
+    }
+
+    #[test]
+    fn peek_map_single_and_empty() {
+        let v = (7..8).peek_map(|n, next| (*n, next.copied()))
+                      .collect::<Vec<_>>();
+        assert_eq!(v, vec![(7, None)]);
+        assert_eq!((0..0).peek_map(|_, _| ()).count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn throttle_spaces_items() {
+        use std::time::{Duration, Instant};
+        let interval = Duration::from_millis(2);
+        let start = Instant::now();
+        let mut stamps = Vec::new();
+        for _ in (0..3).throttle(interval) {
+            stamps.push(Instant::now());
+        }
+        assert_eq!(stamps.len(), 3);
+        assert!(start.elapsed() >= interval * 2);
+        assert!(stamps.windows(2).all(|w| w[1] - w[0] >= interval));
+    }
+
+    #[test]
+    fn compress_selects_items() {
+        let data = ['a', 'b', 'c', 'd'];
+        let v = data.iter()
+                    .compress([true, false, true, false].iter().copied())
+                    .collect::<Vec<_>>();
+        assert_eq!(v, vec![&'a', &'c']);
+    }
+
+    #[test]
+    fn compress_unequal_lengths() {
+        let v = (0..10).compress(vec![false, true, true]).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2]);
+        let v = (0..2).compress(core::iter::repeat(true)).collect::<Vec<_>>();
+        assert_eq!(v, vec![0, 1]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn split_at_predicate_sorted() {
+        let data = vec![1, 2, 4, 5, 7, 8];
+        let (before, after) = data.into_iter().split_at_predicate(|n| *n >= 5);
+        assert_eq!(before.collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert_eq!(after.collect::<Vec<_>>(), vec![5, 7, 8]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn split_at_predicate_after_first() {
+        let (mut before, after) = (1..=8).split_at_predicate(|n| *n >= 5);
+        assert_eq!(before.next(), Some(1));
+        assert_eq!(after.collect::<Vec<_>>(), vec![5, 6, 7, 8]);
+        assert_eq!(before.collect::<Vec<_>>(), vec![2, 3, 4]);
+        let (before, after) = (5..7).split_at_predicate(|n| *n >= 5);
+        assert_eq!(before.count(), 0);
+        assert_eq!(after.count(), 2);
+    }
+
+    #[test]
+    fn take_while_step_ascending_run() {
+        let data = [1, 2, 3, 4, 5, 6, 3, 8, 9];
+        let mut iter = data.iter()
+                           .copied()
+                           .take_while_step(2, |n| *n < 7);
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![1, 3, 5, 3]);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.into_data().0.next(), Some(9));
+    }
+
+    #[test]
+    fn take_while_step_edges() {
+        assert_eq!((0..5).take_while_step(1, |_| true).count(), 5);
+        assert_eq!((0..5).take_while_step(3, |_| false).count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "step must be non-zero")]
+    fn take_while_step_zero() {
+        let _ = (0..3).take_while_step(0, |_| true);
+    }
+
+    #[test]
+    fn transduce_run_length_encoder() {
+        let v = "aaabccdd".chars()
+                          .transduce(None, 
+                                     |run: &mut Option<(char, usize)>, c| {
+                                         match run {
+                                             Some((r, n)) if *r == c => {
+                                                 *n += 1;
+                                                 None
+                                             },
+                                             _ => run.replace((c, 1)),
+                                         }
+                                     }, 
+                                     |run| run)
+                          .collect::<Vec<_>>();
+        assert_eq!(v, vec![('a', 3), ('b', 1), ('c', 2), ('d', 2)]);
+    }
+
+    #[test]
+    fn transduce_many_outputs_and_empty_input() {
+        let v = (1..=3).transduce((), |_, n| vec![n; n], |_| vec![0])
+                       .collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 2, 3, 3, 3, 0]);
+        let mut iter = (0..0).transduce((), |_, n| Some(n), |_| Some(9));
+        assert_eq!(iter.next(), Some(9));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn dedup_within_inside_and_outside_window() {
+        let v = [1, 2, 1, 3, 4, 1, 1].iter()
+                                     .copied()
+                                     .dedup_within(3)
+                                     .collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3, 4, 1]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn dedup_within_small_windows() {
+        let v = [5, 5, 6, 5].iter().dedup_within(1).collect::<Vec<_>>();
+        assert_eq!(v, vec![&5, &6, &5]);
+        let v = [5, 5].iter().dedup_within(0).collect::<Vec<_>>();
+        assert_eq!(v, vec![&5, &5]);
+    }
+
+    #[test]
+    fn prepend_empty_and_non_empty() {
+        let v = core::iter::empty().prepend(7).collect::<Vec<i32>>();
+        assert_eq!(v, vec![7]);
+        let mut iter = "bc".chars().prepend('a');
+        assert_eq!(iter.next(), Some('a'));
+        assert_eq!(iter.data().1, ExtraState::Yielded);
+        assert_eq!(iter.collect::<String>(), "bc");
+    }
+
+    #[test]
+    fn append_empty_and_non_empty() {
+        let v = core::iter::empty().append(7).collect::<Vec<i32>>();
+        assert_eq!(v, vec![7]);
+        let mut iter = "ab".chars().append('c');
+        assert_eq!(iter.by_ref().collect::<String>(), "abc");
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn map_while_state_stops_past_threshold() {
+        let mut iter = [4, 3, 5, 2, 1].iter().map_while_state(0, |sum, &n| {
+            *sum += n;
+            if *sum > 10 { None } else { Some(n) }
+        });
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![4, 3]);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.into_state(), 12);
+    }
+
+    #[test]
+    fn map_while_state_does_not_resume() {
+        let mut iter = [1, -1, 2].iter().map_while_state(0, |calls, &n| {
+            *calls += 1;
+            if n < 0 { None } else { Some(n) }
+        });
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(iter.next(), None);
+        let ScanState { iter: rest, state: calls, .. } = iter.into_data();
+        assert_eq!(calls, 2);
+        assert_eq!(rest.collect::<Vec<_>>(), vec![&2]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn bucket_by_time_with_empty_gap() {
+        use std::time::{Duration, Instant};
+        let t0 = Instant::now();
+        let ms = [0, 3, 9, 10, 19, 42, 45];
+        let v = ms.iter()
+                  .copied()
+                  .bucket_by_time(Duration::from_millis(10), 
+                                  |m| t0 + Duration::from_millis(*m))
+                  .map(|(start, items)| ((start - t0).as_millis(), items))
+                  .collect::<Vec<_>>();
+        assert_eq!(v, vec![(0, vec![0, 3, 9]), 
+                           (10, vec![10, 19]), 
+                           (40, vec![42, 45])]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn bucket_by_time_single_and_empty() {
+        use std::time::{Duration, Instant};
+        let t0 = Instant::now();
+        let width = Duration::from_secs(1);
+        let mut iter = core::iter::once(t0).bucket_by_time(width, |t| *t);
+        assert_eq!(iter.next(), Some((t0, vec![t0])));
+        assert_eq!(iter.next(), None);
+        let n = core::iter::empty().bucket_by_time(width, |t: &Instant| *t)
+                                   .count();
+        assert_eq!(n, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn collect_errors_mixed() {
+        let errors = Rc::new(core::cell::RefCell::new(Vec::new()));
+        let v = vec![Ok(1), Err('a'), Ok(2), Err('b'), Err('c'), Ok(3)];
+        let v = v.into_iter()
+                 .collect_errors(errors.clone())
+                 .collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3]);
+        assert_eq!(*errors.borrow(), vec!['a', 'b', 'c']);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn collect_errors_trailing_and_all_errors() {
+        let errors = Rc::new(core::cell::RefCell::new(Vec::new()));
+        let items = vec![Ok(1), Err(2), Err(3)];
+        let mut iter = items.into_iter().collect_errors(errors.clone());
+        assert_eq!(iter.next(), Some(1));
+        assert!(errors.borrow().is_empty());
+        assert_eq!(iter.next(), None);
+        assert_eq!(*errors.borrow(), vec![2, 3]);
+        let n = vec![Err::<(), _>(4)].into_iter()
+                                     .collect_errors(errors.clone())
+                                     .count();
+        assert_eq!(n, 0);
+        assert_eq!(*errors.borrow(), vec![2, 3, 4]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn transpose_two_by_three() {
+        let rows = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let v = rows.into_iter().transpose().collect::<Vec<_>>();
+        assert_eq!(v, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+        assert_eq!(core::iter::empty::<Vec<u8>>().transpose().count(), 0);
+        assert_eq!(vec![Vec::<u8>::new()].into_iter().transpose().count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic(expected = "rows must all have the same length")]
+    fn transpose_ragged_rows() {
+        let rows = vec![vec![1, 2], vec![3], vec![4, 5]];
+        let _ = rows.into_iter().transpose().next();
+    }
+
+    #[test]
+    fn running_extremes_floats() {
+        let v = [3.0, 1.0, 4.0, 1.5].iter()
+                                    .copied()
+                                    .running_extremes()
+                                    .collect::<Vec<_>>();
+        assert_eq!(v, vec![(3.0, 3.0), (1.0, 3.0), (1.0, 4.0), (1.0, 4.0)]);
+    }
+
+    #[test]
+    fn running_extremes_ignores_nan() {
+        let v = [2.0, f64::NAN, 5.0].iter()
+                                    .copied()
+                                    .running_extremes()
+                                    .collect::<Vec<_>>();
+        assert_eq!(v, vec![(2.0, 2.0), (2.0, 2.0), (2.0, 5.0)]);
+        assert_eq!((0..0).running_extremes().count(), 0);
+    }
+
+    #[test]
+    fn running_extremes_skips_leading_nan() {
+        let v = [f64::NAN, 2.0, 5.0].iter()
+                                    .copied()
+                                    .running_extremes()
+                                    .collect::<Vec<_>>();
+        assert_eq!(v, vec![(2.0, 2.0), (2.0, 5.0)]);
+        assert_eq!([f64::NAN].iter().running_extremes().count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn chunks_stride_overlapping_and_tiling() {
+        let v = (1..=4).chunks_stride(2, 1).collect::<Vec<_>>();
+        assert_eq!(v, vec![vec![1, 2], vec![2, 3], vec![3, 4]]);
+        let v = (1..=7).chunks_stride(3, 3).collect::<Vec<_>>();
+        assert_eq!(v, vec![vec![1, 2, 3], vec![4, 5, 6]]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn chunks_stride_skipping() {
+        let v = (1..=10).chunks_stride(2, 4).collect::<Vec<_>>();
+        assert_eq!(v, vec![vec![1, 2], vec![5, 6], vec![9, 10]]);
+        assert_eq!((1..=3).chunks_stride(4, 1).count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    #[should_panic(expected = "stride must be non-zero")]
+    fn chunks_stride_zero_stride() {
+        let _ = (0..3).chunks_stride(2, 0);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn array_chunks_with_remainder() {
+        let mut iter = IterMapExt::array_chunks::<3>(0..7);
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), 
+                   vec![[0, 1, 2], [3, 4, 5]]);
+        assert_eq!(iter.into_remainder(), vec![6]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn array_chunks_exact_and_empty() {
+        let mut iter = IterMapExt::array_chunks::<2>(0..4);
+        assert_eq!(iter.by_ref().count(), 2);
+        assert!(iter.into_remainder().is_empty());
+        let mut iter = IterMapExt::array_chunks::<4>(0..2);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.into_remainder(), vec![0, 1]);
+    }
+
+    #[test]
+    fn spanned_chars() {
+        let v = "abc".chars().spanned(|_| 1).collect::<Vec<_>>();
+        assert_eq!(v, vec![(0..1, 'a'), (1..2, 'b'), (2..3, 'c')]);
+    }
+
+    #[test]
+    fn spanned_variable_length_tokens() {
+        let mut iter = ["fn", " ", "", "main"].iter().spanned(|s| s.len());
+        assert_eq!(iter.by_ref().map(|(span, _)| span).collect::<Vec<_>>(), 
+                   vec![0..2, 2..3, 3..3, 3..7]);
+        assert_eq!(iter.data().1, 7);
+    }
+
+    #[test]
+    fn report_progress_counts() {
+        let mut reports = Vec::new();
+        let v = (0..10).report_progress(4, |n| reports.push(n))
+                       .collect::<Vec<_>>();
+        assert_eq!(v, (0..10).collect::<Vec<_>>());
+        assert_eq!(reports, vec![4, 8]);
+        let mut reports = Vec::new();
+        assert_eq!((0..3).report_progress(1, |n| reports.push(n)).count(), 3);
+        assert_eq!(reports, vec![1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "report interval must be non-zero")]
+    fn report_progress_zero() {
+        let _ = (0..3).report_progress(0, |_| ());
+    }
+
+    #[test]
+    fn running_sum_saturating_near_max() {
+        let v = [i32::MAX - 1, 1, 1, -5].iter()
+                                        .copied()
+                                        .running_sum_saturating()
+                                        .collect::<Vec<_>>();
+        assert_eq!(v, vec![i32::MAX - 1, i32::MAX, i32::MAX, i32::MAX - 5]);
+        let v = [i32::MIN, -1].iter()
+                              .copied()
+                              .running_sum_saturating()
+                              .collect::<Vec<_>>();
+        assert_eq!(v, vec![i32::MIN, i32::MIN]);
+    }
+
+    #[test]
+    fn running_sum_wrapping_near_max() {
+        let v = [i32::MAX - 1, 1, 1, 1].iter()
+                                       .copied()
+                                       .running_sum_wrapping()
+                                       .collect::<Vec<_>>();
+        assert_eq!(v, vec![i32::MAX - 1, i32::MAX, i32::MIN, i32::MIN + 1]);
+        assert_eq!((0_u8..0).running_sum_wrapping().count(), 0);
+    }
+
+    #[test]
+    fn take_until_sentinel_resumes_past_sentinel() {
+        let polled = [Some(1), Some(2), None, Some(3), None];
+        let mut iter = polled.iter()
+                             .copied()
+                             .take_until_sentinel(Option::is_none);
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![Some(1), Some(2)]);
+        assert_eq!(iter.next(), None);
+        let (mut rest, _) = iter.into_data();
+        assert_eq!(rest.next(), Some(None));
+        let mut iter = rest.take_until_sentinel(Option::is_none);
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![Some(3)]);
+        assert_eq!(iter.into_data().0.collect::<Vec<_>>(), vec![None]);
+    }
+
+    #[test]
+    fn take_until_sentinel_without_sentinel() {
+        let mut iter = (1..4).take_until_sentinel(|n| *n == 0);
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(iter.into_data().0.next(), None);
+        let mut iter = (0..3).take_until_sentinel(|n| *n == 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.into_data().0.collect::<Vec<_>>(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn bucket_by_time_long_gap_stays_aligned() {
+        use std::time::{Duration, Instant};
+        let t0 = Instant::now();
+        let ns = [0, 1, 5_000_000_002, 5_000_000_003];
+        let v = ns.iter()
+                  .copied()
+                  .bucket_by_time(Duration::from_nanos(3), 
+                                  |n| t0 + Duration::from_nanos(*n))
+                  .map(|(start, items)| ((start - t0).as_nanos(), items))
+                  .collect::<Vec<_>>();
+        assert_eq!(v, vec![(0, vec![0, 1]), 
+                           (5_000_000_001, vec![5_000_000_002, 
+                                                5_000_000_003])]);
+    }
+}
@@ -11,7 +11,7 @@
 //! {
 //!     let mut i = 0;
 //!
-//!     let v = [1, 2, 3, 4, 5, 6].iter_map(move |iter| {
+//!     let v = [1, 2, 3, 4, 5, 6].iter().iter_map(move |iter| {
 //!         i += 1;
 //!         if i % 3 == 0 {
 //!             Some(0)
@@ -23,16 +23,129 @@
 //!     assert_eq!(v, vec![1, 2, 0, 3, 4, 0, 5, 6, 0]);
 //! }
 //! ```
+//!
+//! The crate is `no_std` compatible. Disable the default `std` feature to 
+//! build it against `core` only.
+//!
+//! The optional `stream` feature adds `ParamFromFnStream`, an async 
+//! counterpart of `ParamFromFnIter` implementing `futures_core::Stream`. The
+//! optional `rayon` feature adds `.par_iter_map()` for rayon's parallel 
+//! sources.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![allow(clippy::doc_overindented_list_items, clippy::type_complexity)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, collections::VecDeque, rc::Rc, vec::Vec};
+use core::iter::Peekable;
+use core::ops::ControlFlow;
+use core::sync::atomic::AtomicUsize;
+#[cfg(feature = "alloc")]
+use core::sync::atomic::Ordering;
+
+mod adapters;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "stream")]
+mod stream;
+
+pub use adapters::{ExtractState, ExtraState, IntersperseState, IterMapExt, 
+                   OnDrop, OverflowAdd, Pair, Position, ScanState};
+#[cfg(feature = "alloc")]
+pub use adapters::{ArrayChunksState, SharedSide, SplitHalf, Tee, UnzipLefts, 
+                   UnzipRights};
+#[cfg(feature = "rayon")]
+pub use par::ParIterMap;
+#[cfg(feature = "stream")]
+pub use stream::{ParamFromFnStream, StreamMapExt};
 
 /// With ParamFromFnIter you can create iterators simply by calling 
 /// `ParamFromFnIter::new()` and passing it a callback. The callback will be 
 /// invoked when `.next()` is invoked on the iterator returned by `.new()`. 
 ///
+/// There's no `IntoIterator` impl for `&ParamFromFnIter`. Producing an item
+/// means calling an `FnMut` callback with `&mut` data, neither of which can 
+/// be reached through a shared reference, and cloning them to iterate a copy
+/// would silently fork any state the callback captured. `&mut 
+/// ParamFromFnIter` is already an iterator through the standard library's 
+/// blanket impl, so borrow with `.by_ref()` to consume part of the items and
+/// resume afterwards:
+///
+/// ```
+/// use iter_map::IntoIterMap;
+///
+/// let mut iter = (1..=5).iter_map(|iter| iter.next());
+///
+/// for n in iter.by_ref() {
+///     if n == 2 {
+///         break;
+///     }
+/// }
+/// assert_eq!(iter.collect::<Vec<_>>(), vec![3, 4, 5]);
+/// ```
+///
 pub struct ParamFromFnIter<F, D>
 {
     callback: F,
     data: D,
+    size_hint: (usize, Option<usize>),
+    passthrough: Option<Passthrough<D>>,
+}
+
+/// Operations on the inner iterator of a pass-through `ParamFromFnIter`, 
+/// recorded by `ParamFromFnIter::new_passthrough()` while `D` is known to be
+/// an `Iterator`.
+///
+struct Passthrough<D>
+{
+    advance: fn(&mut D, usize) -> usize,
+    size_hint: fn(&D) -> (usize, Option<usize>),
+}
+
+impl<D> Clone for Passthrough<D>
+{
+    fn clone(&self) -> Self
+    {
+        Passthrough { advance: self.advance, size_hint: self.size_hint }
+    }
+}
+
+/// Skips `n` items of `iter` using its own `.nth()` and returns the number
+/// skipped. If `iter` runs out first, that's the lower bound of its size 
+/// hint beforehand, which is exact for iterators that report their length.
+///
+fn advance<I>(iter: &mut I, n: usize) -> usize
+//
+where I: Iterator,
+{
+    if n == 0 {
+        return 0;
+    }
+    let (remaining, _) = iter.size_hint();
+    match iter.nth(n - 1) {
+        Some(_) => n,
+        None => remaining.min(n),
+    }
+}
+
+/// Skips `n` items from the back of `iter` using its own `.nth_back()` and
+/// returns the number skipped, as `advance()` does.
+///
+fn advance_back<I>(iter: &mut I, n: usize) -> usize
+//
+where I: DoubleEndedIterator,
+{
+    if n == 0 {
+        return 0;
+    }
+    let (remaining, _) = iter.size_hint();
+    match iter.nth_back(n - 1) {
+        Some(_) => n,
+        None => remaining.min(n),
+    }
 }
 
 impl<F, D, R> ParamFromFnIter<F, D>
@@ -113,7 +226,344 @@ where F: FnMut(&mut D) -> Option<R>,
     ///    
     pub fn new(data: D, callback: F) -> Self
     {
-        ParamFromFnIter { callback, data }
+        Self::with_size_hint(data, callback, (0, None))
+    }
+
+    /// Creates a new `ParamFromFnIter` that reports `hint` from 
+    /// `.size_hint()`. The hint is decremented as items are produced, so it 
+    /// should describe the total number of items the callback will yield.
+    ///
+    /// `ParamFromFnIter::new()` can't forward the hint of an inner iterator
+    /// stored in `data` because the callback is free to skip or insert items.
+    /// When the caller knows better, this constructor lets adapters such as
+    /// `.collect()` pre-allocate.
+    ///
+    /// ```
+    /// use iter_map::ParamFromFnIter;
+    ///
+    /// let data = [1, 2, 3].iter();
+    /// let iter = ParamFromFnIter::with_size_hint(data, 
+    ///                                            |it| it.next().copied(), 
+    ///                                            (3, Some(3)));
+    ///
+    /// assert_eq!(iter.size_hint(), (3, Some(3)));
+    /// ```
+    ///
+    /// # Arguments
+    /// * `data`      - Data that will be passed to the callback on each 
+    ///                 invocation.
+    /// * `callback`  - The callback that gets invoked when `.next()` is invoked
+    ///                 on the returned iterator.
+    /// * `hint`      - The value to report from `.size_hint()`.
+    ///
+    pub fn with_size_hint(data      : D, 
+                          callback  : F, 
+                          hint      : (usize, Option<usize>)
+                         ) -> Self
+    {
+        ParamFromFnIter { callback, 
+                          data, 
+                          size_hint   : hint, 
+                          passthrough : None }
+    }
+}
+
+impl<F, D, R> ParamFromFnIter<F, D>
+//
+where F: FnMut(&mut D) -> Option<R>,
+      D: Clone,
+{
+    /// Creates a `Resettable` iterator that keeps a copy of `data` so 
+    /// `.reset()` can later restart iteration from the beginning, e.g. to 
+    /// rerun the same pipeline in a benchmark.
+    ///
+    /// ```
+    /// use iter_map::ParamFromFnIter;
+    ///
+    /// let mut iter = ParamFromFnIter::new_resettable(1..=3, |it| it.next());
+    ///
+    /// assert_eq!(iter.by_ref().sum::<i32>(), 6);
+    /// iter.reset();
+    /// assert_eq!(iter.sum::<i32>(), 6);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `data`      - Data that will be passed to the callback on each 
+    ///                 invocation. A clone of it is kept for `.reset()`.
+    /// * `callback`  - The callback that gets invoked when `.next()` is invoked
+    ///                 on the returned iterator.
+    ///
+    pub fn new_resettable(data: D, callback: F) -> Resettable<F, D>
+    {
+        Self::new(data, callback).into_resettable()
+    }
+
+    /// Wraps the iterator in a `Resettable` that keeps a copy of the current
+    /// data, so any `ParamFromFnIter`, including a pass-through one, can be 
+    /// restarted from this point.
+    ///
+    /// ```
+    /// use iter_map::ParamFromFnIter;
+    ///
+    /// let mut iter = ParamFromFnIter::new_passthrough(0..5).into_resettable();
+    ///
+    /// assert_eq!(iter.nth(3), Some(3));
+    /// iter.reset();
+    /// assert_eq!(iter.next(), Some(0));
+    /// ```
+    ///
+    pub fn into_resettable(self) -> Resettable<F, D>
+    {
+        Resettable { snapshot: self.data.clone(), iter: self, produced: 0 }
+    }
+}
+
+impl<D> ParamFromFnIter<fn(&mut D) -> Option<D::Item>, D>
+//
+where D: Iterator,
+{
+    /// Creates a `ParamFromFnIter` whose callback passes the items of `data` 
+    /// through unchanged. Since the callback is known not to skip or insert 
+    /// items, `.size_hint()`, `.nth()` and `.last()` are forwarded to `data`
+    /// instead of invoking the callback for every item.
+    ///
+    /// ```
+    /// use iter_map::ParamFromFnIter;
+    ///
+    /// let mut iter = ParamFromFnIter::new_passthrough(0..1_000_000);
+    ///
+    /// assert_eq!(iter.size_hint(), (1_000_000, Some(1_000_000)));
+    /// assert_eq!(iter.nth(10), Some(10));
+    /// assert_eq!(iter.last(), Some(999_999));
+    /// ```
+    ///
+    /// # Arguments
+    /// * `data`      - The iterator whose items are passed through.
+    ///
+    pub fn new_passthrough(data: D) -> Self
+    {
+        let passthrough = Passthrough { advance   : advance::<D>, 
+                                        size_hint : D::size_hint };
+        ParamFromFnIter { callback    : D::next, 
+                          data, 
+                          size_hint   : (0, None), 
+                          passthrough : Some(passthrough) }
+    }
+}
+
+/// Creates a `ParamFromFnIter` from a callback that takes no data, for pure
+/// generators that keep all their state in captured variables. This mirrors
+/// `std::iter::from_fn()`, but returns this crate's type, so the result
+/// composes with the `ParamFromFnIter` methods.
+///
+/// ```
+/// use iter_map::repeat_with;
+///
+/// let mut state = (0u64, 1u64);
+/// let fib = repeat_with(move || {
+///     let n = state.0;
+///     state = (state.1, state.0 + state.1);
+///     Some(n)
+/// });
+///
+/// assert_eq!(fib.take(10).collect::<Vec<_>>(), 
+///            vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+/// ```
+///
+/// # Arguments
+/// * `f`         - The closure invoked on each `.next()`. Returning `None` 
+///                 signals the end of iteration.
+///
+pub fn repeat_with<G, R>(mut f: G
+                        ) -> ParamFromFnIter<impl FnMut(&mut ()) -> Option<R>, 
+                                             ()>
+//
+where G: FnMut() -> Option<R>,
+{
+    ParamFromFnIter::new((), move |_| f())
+}
+
+impl<R> ParamFromFnIter<fn(&mut ()) -> Option<R>, ()>
+{
+    /// Adapts a `std::iter::from_fn()` style closure, which takes no data, 
+    /// into a `ParamFromFnIter` with `()` for its data. This is the same as
+    /// `repeat_with()`, spelled as a constructor to ease migrating code from
+    /// `from_fn()`. See `.into_std()` for the other direction.
+    ///
+    /// ```
+    /// use iter_map::ParamFromFnIter;
+    ///
+    /// let mut n = 0;
+    /// let iter = ParamFromFnIter::from_std(move || { n += 1; Some(n) });
+    ///
+    /// assert_eq!(iter.take(3).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `g`         - The closure invoked on each `.next()`. Returning `None`
+    ///                 signals the end of iteration.
+    ///
+    pub fn from_std<G>(g: G
+                      ) -> ParamFromFnIter<impl FnMut(&mut ()) -> Option<R>, 
+                                           ()>
+    //
+    where G: FnMut() -> Option<R>,
+    {
+        repeat_with(g)
+    }
+}
+
+impl<D> ParamFromFnIter<fn(&mut D) -> Option<D::Item>, D>
+//
+where D: DoubleEndedIterator,
+{
+    /// Like `ParamFromFnIter::new_passthrough()`, but the callback takes the
+    /// items from the back of `data`, yielding them in reverse without 
+    /// collecting them. `.nth()` and `.last()` skip ahead with the inner 
+    /// iterator's `.nth_back()`.
+    ///
+    /// ```
+    /// use iter_map::ParamFromFnIter;
+    ///
+    /// let mut iter = ParamFromFnIter::rev_passthrough([1, 2, 3, 4].iter());
+    ///
+    /// assert_eq!(iter.next(), Some(&4));
+    /// assert_eq!(iter.size_hint(), (3, Some(3)));
+    /// assert_eq!(iter.last(), Some(&1));
+    /// ```
+    ///
+    /// # Arguments
+    /// * `data`      - The iterator whose items are passed through in reverse.
+    ///
+    pub fn rev_passthrough(data: D) -> Self
+    {
+        let passthrough = Passthrough { advance   : advance_back::<D>, 
+                                        size_hint : D::size_hint };
+        ParamFromFnIter { callback    : D::next_back, 
+                          data, 
+                          size_hint   : (0, None), 
+                          passthrough : Some(passthrough) }
+    }
+}
+
+impl<F, D> ParamFromFnIter<F, D>
+{
+    /// Returns a reference to the data passed to the callback. The callback 
+    /// isn't invoked.
+    ///
+    pub fn data(&self) -> &D
+    {
+        &self.data
+    }
+
+    /// Returns a mutable reference to the data passed to the callback. This 
+    /// can be used to inspect a `Peekable` inner iterator or to adjust state
+    /// between phases of iteration. The callback isn't invoked.
+    ///
+    /// ```
+    /// use iter_map::ParamFromFnIter;
+    ///
+    /// let mut iter = ParamFromFnIter::new(0, |n| { *n += 1; Some(*n) });
+    ///
+    /// assert_eq!(iter.next(), Some(1));
+    /// *iter.data_mut() = 10;
+    /// assert_eq!(iter.next(), Some(11));
+    /// ```
+    ///
+    pub fn data_mut(&mut self) -> &mut D
+    {
+        &mut self.data
+    }
+
+    /// Swaps `new` into the data passed to the callback and returns the old 
+    /// data. This lets one configured pipeline be reused over several inner
+    /// iterators without rebuilding the callback.
+    ///
+    /// Only the data is replaced. State the callback has captured, such as a
+    /// counter, carries over unchanged; the same goes for the size hint given
+    /// to `ParamFromFnIter::with_size_hint()`.
+    ///
+    /// ```
+    /// use iter_map::IntoIterMap;
+    ///
+    /// let mut iter = (1..=2).iter_map(|iter| iter.next().map(|n| n * 10));
+    ///
+    /// assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![10, 20]);
+    /// iter.replace_data(5..=6);
+    /// assert_eq!(iter.collect::<Vec<_>>(), vec![50, 60]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `new`       - The data to pass to the callback from now on.
+    ///
+    pub fn replace_data(&mut self, new: D) -> D
+    {
+        core::mem::replace(&mut self.data, new)
+    }
+
+    /// Consumes the iterator and returns one with the same callback and its 
+    /// data transformed by `f`. The callback's signature must still accept 
+    /// the new data type for the result to be an `Iterator`, so this is 
+    /// mainly useful for data the callback sees through a trait object or a
+    /// newtype, such as a boxed inner iterator. The size hint resets to 
+    /// `(0, None)` and the pass-through fast paths are dropped, since neither
+    /// is known to hold for the new data.
+    ///
+    /// ```
+    /// use iter_map::ParamFromFnIter;
+    ///
+    /// type Inner = Box<dyn Iterator<Item = i32>>;
+    ///
+    /// let data: Inner = Box::new(vec![0, 1, 2].into_iter());
+    /// let iter = ParamFromFnIter::new(data, |it: &mut Inner| it.next());
+    ///
+    /// // Wrap the raw iterator in a `Peekable` to skip a leading 0 header.
+    /// let iter = iter.map_data(|it| {
+    ///     let mut it = it.peekable();
+    ///     it.next_if_eq(&0);
+    ///     Box::new(it) as Inner
+    /// });
+    ///
+    /// assert_eq!(iter.collect::<Vec<_>>(), vec![1, 2]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `f`         - The closure that transforms the data.
+    ///
+    pub fn map_data<D2, G>(self, f: G) -> ParamFromFnIter<F, D2>
+    //
+    where G: FnOnce(D) -> D2,
+    {
+        ParamFromFnIter { callback    : self.callback, 
+                          data        : f(self.data), 
+                          size_hint   : (0, None), 
+                          passthrough : None }
+    }
+
+    /// Consumes the iterator and returns its data. Since the struct's fields
+    /// are private, this and `.into_parts()` are the only way to reclaim an
+    /// inner iterator, for instance to continue consuming items the callback
+    /// left behind.
+    ///
+    /// ```
+    /// use iter_map::IntoIterMap;
+    ///
+    /// let mut iter = (1..=5).iter_map(|iter| iter.next());
+    ///
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.into_data().collect::<Vec<_>>(), vec![2, 3, 4, 5]);
+    /// ```
+    ///
+    pub fn into_data(self) -> D
+    {
+        self.data
+    }
+
+    /// Consumes the iterator and returns its callback and data.
+    ///
+    pub fn into_parts(self) -> (F, D)
+    {
+        (self.callback, self.data)
     }
 }
 
@@ -130,64 +580,1278 @@ where F: FnMut(&mut D) -> Option<R>,
     ///
     fn next(&mut self) -> Option<Self::Item>
     {
-        (self.callback)(&mut self.data)
+        let item = (self.callback)(&mut self.data);
+        if item.is_some() {
+            let (lower, upper) = self.size_hint;
+            self.size_hint = (lower.saturating_sub(1), 
+                              upper.map(|n| n.saturating_sub(1)));
+        }
+        item
+    }
+
+    /// Returns the hint given to `ParamFromFnIter::with_size_hint()`, less 
+    /// the number of items produced so far. Defaults to `(0, None)`. The 
+    /// inner iterator's hint is returned for pass-through iterators.
+    ///
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        match &self.passthrough {
+            Some(passthrough) => (passthrough.size_hint)(&self.data),
+            None => self.size_hint,
+        }
+    }
+
+    /// Returns the `n`th item. Pass-through iterators skip ahead using the 
+    /// inner iterator's own `.nth()`.
+    ///
+    fn nth(&mut self, n: usize) -> Option<Self::Item>
+    {
+        if self.skip_items(n) < n {
+            return None;
+        }
+        self.next()
+    }
+
+    /// Returns the last item. Pass-through iterators whose inner iterator 
+    /// reports an exact size skip straight to its last item.
+    ///
+    fn last(mut self) -> Option<Self::Item>
+    {
+        if let Some(passthrough) = &self.passthrough {
+            if let (len, Some(upper)) = (passthrough.size_hint)(&self.data) {
+                if len == upper && len > 1 {
+                    (passthrough.advance)(&mut self.data, len - 1);
+                }
+            }
+        }
+        let mut last = None;
+        for item in self {
+            last = Some(item);
+        }
+        last
+    }
+
+    /// Counts the items by driving the callback until it returns `None`. 
+    /// Each item is dropped as soon as it's produced and the size hint isn't
+    /// maintained along the way.
+    ///
+    fn count(mut self) -> usize
+    {
+        let mut count = 0;
+        while (self.callback)(&mut self.data).is_some() {
+            count += 1;
+        }
+        count
     }
 }
 
-/// A trait to add the `.iter_map()` method to any existing class.
-///
-pub trait IntoIterMap<F, I, R, T>
+impl<F, D, R> ParamFromFnIter<F, D>
 //
-where F: FnMut(&mut I) -> Option<R>,
-      I: Iterator<Item = T>,
+where F: FnMut(&mut D) -> Option<R>,
 {
-    /// Returns a `ParamFromFnIter` iterator which wraps the iterator it's 
-    /// invoked on.
-    ///
-    /// # Arguments
-    /// * `callback`  - The callback that gets invoked by `.next()`.
-    ///                 This callback is passed the original iterator as its
-    ///                 parameter.
+    /// Skips up to `n` items and returns the number skipped, which is less 
+    /// than `n` only if the iterator ran out. Pass-through iterators skip 
+    /// ahead using the inner iterator's own `.nth()`.
     ///
-    fn iter_map(self, callback: F) -> ParamFromFnIter<F, I>;
+    fn skip_items(&mut self, n: usize) -> usize
+    {
+        match &self.passthrough {
+            Some(passthrough) => (passthrough.advance)(&mut self.data, n),
+            None => (0..n).find(|_| self.next().is_none()).unwrap_or(n),
+        }
+    }
 }
 
-/// Adds `.iter_map()` method to all IntoIterator classes.
-///
-impl<F, I, J, R, T> IntoIterMap<F, I, R, T> for J
+impl<F, D> ParamFromFnIter<F, D>
 //
-where F: FnMut(&mut I) -> Option<R>,
-      I: Iterator<Item = T>,
-      J: IntoIterator<Item = T, IntoIter = I>,
+where D: ExactSizeIterator,
 {
-    /// Returns an iterator that invokes the callback in `.next()`, passing it
-    /// the original iterator as an argument. The callback can return any
-    /// arbitrary type within an `Option`.
+    /// Returns the number of items remaining in the inner iterator without 
+    /// invoking the callback. This is only the number of items the iterator
+    /// would yield when the callback is a pure pass-through, such as
+    /// `|iter| iter.next()`; callbacks that skip or insert items make the 
+    /// result meaningless.
     ///
     /// ```
     /// use iter_map::IntoIterMap;
     ///
-    /// let mut b = true;
-    ///
-    /// let s = "hello world!".chars().peekable().iter_map(|iter| {
-    ///     if let Some(&ch) = iter.peek() {
-    ///         if ch == 'o' && b {
-    ///             b = false;
-    ///             Some('0')
-    ///         } else {
-    ///             b = true;
-    ///             iter.next()
-    ///         }
-    ///     } else { None }}).collect::<String>();
+    /// let iter = (0..1000).iter_map(|iter| iter.next());
     ///
-    /// assert_eq!(&s, "hell0o w0orld!");
+    /// assert_eq!(iter.count_via_data(), 1000);
     /// ```
-    fn iter_map(self, callback: F) -> ParamFromFnIter<F, I>
+    ///
+    pub fn count_via_data(self) -> usize
     {
-        ParamFromFnIter::new(self.into_iter(), callback)
+        self.data.len()
     }
 }
 
+/// Implements Clone for ParamFromFnIter when both the callback and data are 
+/// Clone. Cloning a closure clones whatever it captured at that moment, so 
+/// the clone resumes from the same logical position as the original and the
+/// two then advance independently.
+///
+impl<F, D> Clone for ParamFromFnIter<F, D>
+//
+where F: Clone,
+      D: Clone,
+{
+    fn clone(&self) -> Self
+    {
+        ParamFromFnIter { callback    : self.callback.clone(), 
+                          data        : self.data.clone(), 
+                          size_hint   : self.size_hint, 
+                          passthrough : self.passthrough.clone() }
+    }
+}
+
+/// Implements Debug for ParamFromFnIter. The callback is printed as a 
+/// `<closure>` placeholder since closures don't implement Debug. A fallback
+/// impl for data that isn't Debug would overlap with this one, so such data
+/// has to be wrapped in a Debug newtype first.
+///
+impl<F, D> core::fmt::Debug for ParamFromFnIter<F, D>
+//
+where D: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        f.debug_struct("ParamFromFnIter")
+         .field("callback", &format_args!("<closure>"))
+         .field("data", &self.data)
+         .field("size_hint", &self.size_hint)
+         .finish()
+    }
+}
+
+impl<F, D, R> ParamFromFnIter<F, D>
+//
+where F: FnMut(&mut D) -> Option<R>,
+{
+    /// Wraps the iterator in a `Fused` iterator that stops invoking the 
+    /// callback once it has returned `None`. Unlike `.fuse()`, the wrapper is
+    /// named by this crate and implements `FusedIterator` so std adapters can 
+    /// rely on it.
+    ///
+    /// ```
+    /// use iter_map::IntoIterMap;
+    ///
+    /// let mut done = false;
+    ///
+    /// let mut iter = [1, 2].iter().iter_map(move |iter| {
+    ///     assert!(!done, "callback invoked after returning None");
+    ///     let item = iter.next();
+    ///     done = item.is_none();
+    ///     item
+    /// }).fuse_callback();
+    ///
+    /// assert_eq!(iter.by_ref().count(), 2);
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    ///
+    pub fn fuse_callback(self) -> Fused<F, D>
+    {
+        Fused { iter: self, done: false }
+    }
+
+    /// Wraps the iterator in a `PeekableParamFromFn`, which can peek at the
+    /// next item while keeping the data accessors that `.peekable()` would 
+    /// hide.
+    ///
+    /// ```
+    /// use iter_map::IntoIterMap;
+    ///
+    /// let mut iter = (1..=3).iter_map(|iter| iter.next()).into_peekable();
+    ///
+    /// assert_eq!(iter.peek(), Some(&1));
+    /// assert_eq!(iter.data().clone().next(), Some(2));
+    /// assert_eq!(iter.next(), Some(1));
+    /// ```
+    ///
+    pub fn into_peekable(self) -> PeekableParamFromFn<F, D, R>
+    {
+        PeekableParamFromFn { iter: self, peeked: None }
+    }
+
+    /// Stacks a second stage on the iterator: returns a `ParamFromFnIter` 
+    /// whose data is this iterator and whose callback is `next_callback`. 
+    /// This is the same as calling `.iter_map()` on it, but keeps the named 
+    /// type so stages can be built up fluently, and `.data()` on the result 
+    /// reaches the previous stage.
+    ///
+    /// ```
+    /// use iter_map::IntoIterMap;
+    ///
+    /// let v = (1..=4).iter_map(|iter| iter.next().map(|n| n * 10))
+    ///                .compose(|iter| iter.next().map(|n| n + 1))
+    ///                .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![11, 21, 31, 41]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `next_callback` - The callback of the second stage. It's passed 
+    ///                     this iterator as its parameter.
+    ///
+    pub fn compose<G, R2>(self, next_callback: G) -> ParamFromFnIter<G, Self>
+    //
+    where G: FnMut(&mut Self) -> Option<R2>,
+    {
+        ParamFromFnIter::new(self, next_callback)
+    }
+
+    /// Converts the iterator into a closure taking no arguments that yields
+    /// its items, which can be passed to `std::iter::from_fn()` or any other
+    /// API expecting a generator closure. The closure owns the iterator, 
+    /// data and callback included.
+    ///
+    /// ```
+    /// use iter_map::IntoIterMap;
+    ///
+    /// let next = (1..=3).iter_map(|iter| iter.next()).into_std();
+    ///
+    /// let v = std::iter::from_fn(next).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 2, 3]);
+    /// ```
+    ///
+    pub fn into_std(mut self) -> impl FnMut() -> Option<R>
+    {
+        move || self.next()
+    }
+
+    /// Wraps the callback so each invocation is recorded in `counters`: 
+    /// `calls` counts every invocation, `yielded` those that returned an 
+    /// item, and `skipped` those that returned `None`. The data is left as 
+    /// is. The pass-through fast paths are dropped so no item bypasses the 
+    /// count.
+    ///
+    /// ```
+    /// use iter_map::{Counters, IntoIterMap};
+    /// use std::rc::Rc;
+    /// use std::sync::atomic::Ordering;
+    ///
+    /// let counters = Rc::new(Counters::default());
+    /// let n = (1..=3).iter_map(|iter| iter.next())
+    ///                .instrument(counters.clone())
+    ///                .count();
+    ///
+    /// assert_eq!(n, 3);
+    /// assert_eq!(counters.calls.load(Ordering::Relaxed), 4);
+    /// assert_eq!(counters.skipped.load(Ordering::Relaxed), 1);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `counters`  - The counters to record invocations in.
+    ///
+    #[cfg(feature = "alloc")]
+    pub fn instrument(self, 
+                      counters: Rc<Counters>
+                     ) -> ParamFromFnIter<impl FnMut(&mut D) -> Option<R>, D>
+    {
+        let mut callback = self.callback;
+        ParamFromFnIter { callback    : move |data: &mut D| {
+                              let item = callback(data);
+                              counters.record(item.is_some());
+                              item
+                          }, 
+                          data        : self.data, 
+                          size_hint   : self.size_hint, 
+                          passthrough : None }
+    }
+
+    /// Returns an iterator that pairs each item with a `Stats` snapshot of 
+    /// the counts up to and including that item. The counts are kept next to
+    /// the original data, as `(data, stats)`, and the callback is wrapped to 
+    /// update them on every invocation.
+    ///
+    /// `callback_calls` outgrows `produced` only by invocations that returned
+    /// `None`; inner items a callback passes over within a single invocation
+    /// aren't visible to the wrapper.
+    ///
+    /// ```
+    /// use iter_map::IntoIterMap;
+    ///
+    /// let mut iter = (1..=2).iter_map(|iter| iter.next()).with_stats();
+    ///
+    /// let (stats, item) = iter.next().unwrap();
+    /// assert_eq!((stats.produced, stats.callback_calls, item), (1, 1, 1));
+    /// assert_eq!(iter.by_ref().count(), 1);
+    /// assert_eq!(iter.data().1.callback_calls, 3);
+    /// ```
+    ///
+    pub fn with_stats(self
+                     ) -> ParamFromFnIter<impl FnMut(&mut (D, Stats)) 
+                                                 -> Option<(Stats, R)>, 
+                                          (D, Stats)>
+    {
+        let mut callback = self.callback;
+        ParamFromFnIter { callback    : move |(data, stats): &mut (D, Stats)| {
+                              stats.callback_calls += 1;
+                              let item = callback(data)?;
+                              stats.produced += 1;
+                              Some((*stats, item))
+                          }, 
+                          data        : (self.data, Stats::default()), 
+                          size_hint   : self.size_hint, 
+                          passthrough : None }
+    }
+}
+
+/// The counts yielded with each item by `ParamFromFnIter::with_stats()`.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats
+{
+    /// The number of items produced so far.
+    pub produced: usize,
+    /// The number of times the callback was invoked so far.
+    pub callback_calls: usize,
+}
+
+/// A position in a `Resettable` iterator, taken by `.checkpoint()` and 
+/// returned to by `.restore()`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint(usize);
+
+/// An iterator returned by `ParamFromFnIter::new_resettable()` and 
+/// `ParamFromFnIter::into_resettable()`. It keeps a copy of the data next to
+/// the wrapped iterator so `.reset()` can restart it, which plain 
+/// `ParamFromFnIter`s don't pay for.
+///
+#[derive(Clone)]
+pub struct Resettable<F, D>
+{
+    iter: ParamFromFnIter<F, D>,
+    snapshot: D,
+    produced: usize,
+}
+
+impl<F, D, R> Resettable<F, D>
+//
+where F: FnMut(&mut D) -> Option<R>,
+      D: Clone,
+{
+    /// Restores the data to the copy taken when the iterator was made 
+    /// resettable. Only the data is restored; state the callback has 
+    /// captured, such as a counter, carries over unchanged.
+    ///
+    pub fn reset(&mut self)
+    {
+        self.iter.data = self.snapshot.clone();
+        self.produced = 0;
+    }
+
+    /// Returns a `Checkpoint` recording how many items have been produced 
+    /// since the iterator was created or last reset.
+    ///
+    pub fn checkpoint(&self) -> Checkpoint
+    {
+        Checkpoint(self.produced)
+    }
+
+    /// Resets the iterator and fast-forwards it to `checkpoint` by producing
+    /// and discarding as many items as had been produced when the checkpoint
+    /// was taken. This recreates the same position only if the callback 
+    /// produces the same items from the same data each time; as with 
+    /// `.reset()`, state the callback has captured isn't restored.
+    ///
+    /// ```
+    /// use iter_map::ParamFromFnIter;
+    ///
+    /// let mut iter = ParamFromFnIter::new_resettable(0..10, |it| it.next());
+    ///
+    /// iter.by_ref().take(3).for_each(drop);
+    /// let checkpoint = iter.checkpoint();
+    /// assert_eq!(iter.nth(4), Some(7));
+    /// iter.restore(checkpoint);
+    /// assert_eq!(iter.next(), Some(3));
+    /// ```
+    ///
+    /// # Arguments
+    /// * `checkpoint`    - A checkpoint taken from this iterator.
+    ///
+    pub fn restore(&mut self, checkpoint: Checkpoint)
+    {
+        self.reset();
+        for _ in 0..checkpoint.0 {
+            if self.next().is_none() {
+                break;
+            }
+        }
+    }
+}
+
+impl<F, D> Resettable<F, D>
+{
+    /// Returns a reference to the data passed to the callback.
+    ///
+    pub fn data(&self) -> &D
+    {
+        &self.iter.data
+    }
+
+    /// Consumes the wrapper and returns the iterator it wraps, dropping the
+    /// copy of the data.
+    ///
+    pub fn into_inner(self) -> ParamFromFnIter<F, D>
+    {
+        self.iter
+    }
+}
+
+/// Implements Iterator for Resettable by forwarding to the wrapped 
+/// iterator and counting the items it produces for `.checkpoint()`.
+///
+impl<F, D, R> Iterator for Resettable<F, D>
+//
+where F: FnMut(&mut D) -> Option<R>,
+{
+    type Item = R;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        let item = self.iter.next();
+        if item.is_some() {
+            self.produced += 1;
+        }
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        self.iter.size_hint()
+    }
+
+    /// Returns the `n`th item, counting only the items actually skipped 
+    /// when the iterator runs out first.
+    ///
+    fn nth(&mut self, n: usize) -> Option<Self::Item>
+    {
+        let skipped = self.iter.skip_items(n);
+        self.produced = self.produced.saturating_add(skipped);
+        if skipped < n {
+            return None;
+        }
+        self.next()
+    }
+}
+
+/// Implements Debug for Resettable, showing the wrapped iterator and the 
+/// copy of the data.
+///
+impl<F, D> core::fmt::Debug for Resettable<F, D>
+//
+where D: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        f.debug_struct("Resettable")
+         .field("iter", &self.iter)
+         .field("snapshot", &self.snapshot)
+         .field("produced", &self.produced)
+         .finish()
+    }
+}
+
+/// An iterator returned by `ParamFromFnIter::into_peekable()`. A peeked item
+/// is produced by the callback and buffered until `.next()` returns it, so 
+/// the data seen through `.data()` may be one item ahead of the output.
+///
+pub struct PeekableParamFromFn<F, D, R>
+{
+    iter: ParamFromFnIter<F, D>,
+    peeked: Option<Option<R>>,
+}
+
+impl<F, D, R> PeekableParamFromFn<F, D, R>
+//
+where F: FnMut(&mut D) -> Option<R>,
+{
+    /// Returns a reference to the next item without consuming it. The 
+    /// callback is invoked only if no item is buffered yet.
+    ///
+    pub fn peek(&mut self) -> Option<&R>
+    {
+        let iter = &mut self.iter;
+        self.peeked.get_or_insert_with(|| iter.next()).as_ref()
+    }
+
+    /// Returns a reference to the data passed to the callback.
+    ///
+    pub fn data(&self) -> &D
+    {
+        self.iter.data()
+    }
+
+    /// Returns a mutable reference to the data passed to the callback. An 
+    /// item that has already been peeked isn't affected.
+    ///
+    pub fn data_mut(&mut self) -> &mut D
+    {
+        self.iter.data_mut()
+    }
+}
+
+/// Implements Iterator for PeekableParamFromFn.
+///
+impl<F, D, R> Iterator for PeekableParamFromFn<F, D, R>
+//
+where F: FnMut(&mut D) -> Option<R>,
+{
+    type Item = R;
+
+    /// Returns the peeked item if there is one, otherwise invokes the 
+    /// callback.
+    ///
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        match self.peeked.take() {
+            Some(item) => item,
+            None => self.iter.next(),
+        }
+    }
+
+    /// Returns the wrapped iterator's hint, counting a peeked item.
+    ///
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        let (lower, upper) = self.iter.size_hint();
+        match &self.peeked {
+            Some(None) => (0, Some(0)),
+            Some(Some(_)) => (lower.saturating_add(1), 
+                              upper.and_then(|n| n.checked_add(1))),
+            None => (lower, upper),
+        }
+    }
+}
+
+/// Invocation counts recorded by `ParamFromFnIter::instrument()`. The fields
+/// are atomic so a snapshot of the counts can be read, or the counters moved,
+/// across threads.
+///
+#[derive(Debug, Default)]
+pub struct Counters
+{
+    /// The number of times the callback was invoked.
+    pub calls: AtomicUsize,
+    /// The number of invocations that returned an item.
+    pub yielded: AtomicUsize,
+    /// The number of invocations that returned `None`.
+    pub skipped: AtomicUsize,
+}
+
+#[cfg(feature = "alloc")]
+impl Counters
+{
+    /// Records one invocation and whether it returned an item.
+    ///
+    fn record(&self, yielded: bool)
+    {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        if yielded {
+            self.yielded.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// An iterator returned by `ParamFromFnIter::fuse_callback()`. Once the 
+/// callback returns `None`, every subsequent `.next()` returns `None` without
+/// invoking the callback again.
+///
+pub struct Fused<F, D>
+{
+    iter: ParamFromFnIter<F, D>,
+    done: bool,
+}
+
+/// Implements Iterator for Fused.
+///
+impl<F, D, R> Iterator for Fused<F, D>
+//
+where F: FnMut(&mut D) -> Option<R>,
+{
+    type Item = R;
+
+    /// Invokes the wrapped iterator's callback until it first returns `None`.
+    ///
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.done {
+            return None;
+        }
+        let item = self.iter.next();
+        self.done = item.is_none();
+        item
+    }
+
+    /// Returns `(0, Some(0))` once exhausted, otherwise the wrapped 
+    /// iterator's hint.
+    ///
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        if self.done { (0, Some(0)) } else { self.iter.size_hint() }
+    }
+}
+
+/// Implements FusedIterator for Fused.
+///
+impl<F, D, R> core::iter::FusedIterator for Fused<F, D>
+//
+where F: FnMut(&mut D) -> Option<R>,
+{
+}
+
+/// A double-ended variant of `ParamFromFnIter`. A second callback is invoked
+/// by `.next_back()`, letting the iterator be consumed from both ends.
+///
+/// Both callbacks receive the same `&mut D`. They must coordinate so the 
+/// front and back never hand out the same item twice; for example, by popping
+/// from opposite ends of a shared `VecDeque`.
+///
+pub struct ParamFromFnIterDE<F, B, D>
+{
+    callback: F,
+    back_callback: B,
+    data: D,
+}
+
+impl<F, B, D, R> ParamFromFnIterDE<F, B, D>
+//
+where F: FnMut(&mut D) -> Option<R>,
+      B: FnMut(&mut D) -> Option<R>,
+{
+    /// Creates a new `ParamFromFnIterDE` iterator instance.
+    ///
+    /// ```
+    /// use iter_map::ParamFromFnIterDE;
+    /// use std::collections::VecDeque;
+    ///
+    /// let data = (1..=5).collect::<VecDeque<_>>();
+    /// let mut iter = ParamFromFnIterDE::new(data, 
+    ///                                       |d| d.pop_front(), 
+    ///                                       |d| d.pop_back());
+    ///
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.next_back(), Some(5));
+    /// assert_eq!(iter.collect::<Vec<_>>(), vec![2, 3, 4]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `data`          - Data that will be passed to both callbacks on each
+    ///                     invocation.
+    /// * `callback`      - The callback that gets invoked when `.next()` is 
+    ///                     invoked on the returned iterator.
+    /// * `back_callback` - The callback that gets invoked when `.next_back()`
+    ///                     is invoked on the returned iterator.
+    ///
+    pub fn new(data: D, callback: F, back_callback: B) -> Self
+    {
+        ParamFromFnIterDE { callback, back_callback, data }
+    }
+}
+
+/// Implements Iterator for ParamFromFnIterDE.
+///
+impl<F, B, D, R> Iterator for ParamFromFnIterDE<F, B, D>
+//
+where F: FnMut(&mut D) -> Option<R>,
+      B: FnMut(&mut D) -> Option<R>,
+{
+    type Item = R;
+
+    /// Invokes the front callback, passing it `&mut self.data`.
+    ///
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        (self.callback)(&mut self.data)
+    }
+}
+
+/// Implements DoubleEndedIterator for ParamFromFnIterDE.
+///
+impl<F, B, D, R> DoubleEndedIterator for ParamFromFnIterDE<F, B, D>
+//
+where F: FnMut(&mut D) -> Option<R>,
+      B: FnMut(&mut D) -> Option<R>,
+{
+    /// Invokes the back callback, passing it `&mut self.data`.
+    ///
+    fn next_back(&mut self) -> Option<Self::Item>
+    {
+        (self.back_callback)(&mut self.data)
+    }
+}
+
+/// The iterator passed to `.iter_map_lookahead()` callbacks. It yields the 
+/// buffered lookahead items before pulling more from the original iterator, 
+/// so consuming it advances the lookahead window.
+///
+#[cfg(feature = "alloc")]
+pub struct Lookahead<I>
+//
+where I: Iterator,
+{
+    buffer: VecDeque<I::Item>,
+    iter: I,
+}
+
+#[cfg(feature = "alloc")]
+impl<I> Lookahead<I>
+//
+where I: Iterator,
+{
+    /// Pulls items from the original iterator until `n` are buffered or it 
+    /// runs out.
+    ///
+    fn fill(&mut self, n: usize)
+    {
+        while self.buffer.len() < n {
+            match self.iter.next() {
+                Some(item) => self.buffer.push_back(item),
+                None => break,
+            }
+        }
+    }
+}
+
+/// Implements Iterator for Lookahead.
+///
+#[cfg(feature = "alloc")]
+impl<I> Iterator for Lookahead<I>
+//
+where I: Iterator,
+{
+    type Item = I::Item;
+
+    /// Returns the next buffered item, or the next item of the original 
+    /// iterator if none are buffered.
+    ///
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        self.buffer.pop_front().or_else(|| self.iter.next())
+    }
+}
+
+/// The iterator type returned by the crate's callback variants and adapters.
+/// The callback is a plain function pointer and all of its state, including
+/// any user provided closures, lives in `data`.
+///
+pub type Adapter<D, R> = ParamFromFnIter<fn(&mut D) -> Option<R>, D>;
+
+/// The boxed inner iterator passed to `.iter_map_dyn()` callbacks. The 
+/// callback can assign a new box to it to switch to another source.
+///
+#[cfg(feature = "alloc")]
+pub type DynIter<'a, T> = Box<dyn Iterator<Item = T> + 'a>;
+
+/// A trait to add the `.iter_map()` method to any existing class.
+///
+pub trait IntoIterMap<F, I, R, T>
+//
+where F: FnMut(&mut I) -> Option<R>,
+      I: Iterator<Item = T>,
+{
+    /// Returns a `ParamFromFnIter` iterator which wraps the iterator it's 
+    /// invoked on.
+    ///
+    /// # Arguments
+    /// * `callback`  - The callback that gets invoked by `.next()`.
+    ///                 This callback is passed the original iterator as its
+    ///                 parameter.
+    ///
+    fn iter_map(self, callback: F) -> ParamFromFnIter<F, I>;
+}
+
+/// A trait adding the callback variants of `.iter_map()`, such as 
+/// `.iter_map_indexed()` and `.try_iter_map()`, to any existing class. 
+/// They're kept apart from `IntoIterMap`, whose generic parameters are fixed
+/// by its single method's callback.
+///
+pub trait IterMapVariants: IntoIterator + Sized
+{
+    /// Like `.iter_map()`, but the callback is also passed the number of 
+    /// items produced so far, starting at 0. The index only advances when 
+    /// the callback returns `Some`, matching `.enumerate()`.
+    ///
+    /// # Arguments
+    /// * `callback`  - The callback that gets invoked by `.next()`.
+    ///                 This callback is passed the index and the original
+    ///                 iterator as its parameters.
+    ///
+    fn iter_map_indexed<G, R>(self, 
+                              callback: G
+                             ) -> Adapter<(Self::IntoIter, usize, G), R>
+    //
+    where G: FnMut(usize, &mut Self::IntoIter) -> Option<R>;
+
+    /// Like `.iter_map()`, but for fallible callbacks. The first `Err` the 
+    /// callback returns is yielded, after which the iterator returns `None` 
+    /// without invoking the callback again.
+    ///
+    /// # Arguments
+    /// * `callback`  - The callback that gets invoked by `.next()`.
+    ///                 This callback is passed the original iterator as its
+    ///                 parameter.
+    ///
+    fn try_iter_map<G, R, E>(self, 
+                             callback: G
+                            ) -> Adapter<(Self::IntoIter, bool, G), 
+                                         Result<R, E>>
+    //
+    where G: FnMut(&mut Self::IntoIter) -> Option<Result<R, E>>;
+
+    /// Like `.iter_map()`, but the callback returns an `IntoIterator` whose 
+    /// items are yielded one by one before the callback is invoked again. An
+    /// empty result yields nothing for that step; `None` ends iteration.
+    ///
+    /// # Arguments
+    /// * `callback`  - The callback that gets invoked by `.next()` whenever
+    ///                 the previously returned items are used up.
+    ///
+    fn iter_flat_map<G, K>(self, 
+                           callback: G
+                          ) -> Adapter<(Self::IntoIter, 
+                                        Option<K::IntoIter>, 
+                                        G), 
+                                       K::Item>
+    //
+    where G: FnMut(&mut Self::IntoIter) -> Option<K>,
+          K: IntoIterator;
+
+    /// Like `.iter_map()`, but the original iterator is wrapped in a 
+    /// `Peekable` first so the callback can always look ahead.
+    ///
+    /// # Arguments
+    /// * `callback`  - The callback that gets invoked by `.next()`.
+    ///                 This callback is passed the peekable original iterator
+    ///                 as its parameter.
+    ///
+    fn iter_map_peekable<G, R>(self, 
+                               callback: G
+                              ) -> ParamFromFnIter<G, Peekable<Self::IntoIter>>
+    //
+    where G: FnMut(&mut Peekable<Self::IntoIter>) -> Option<R>;
+
+    /// Like `.iter_map()`, but the callback is also passed a slice of up to 
+    /// `n` upcoming items. The slice is a copy of the lookahead buffer, which 
+    /// is refilled lazily before each callback invocation; near the end of 
+    /// the stream it may be shorter than `n`.
+    ///
+    /// # Arguments
+    /// * `n`         - The number of items to look ahead.
+    /// * `callback`  - The callback that gets invoked by `.next()`.
+    ///                 This callback is passed the upcoming items and a 
+    ///                 `Lookahead` iterator that yields them.
+    ///
+    #[cfg(feature = "alloc")]
+    fn iter_map_lookahead<G, R>(self, 
+                                n        : usize, 
+                                callback : G
+                               ) -> Adapter<(Lookahead<Self::IntoIter>, 
+                                             Vec<Self::Item>, 
+                                             usize, 
+                                             G), 
+                                            R>
+    //
+    where G: FnMut(&[Self::Item], &mut Lookahead<Self::IntoIter>) -> Option<R>,
+          Self::Item: Clone;
+
+    /// Like `.iter_map()`, but the callback returns a `ControlFlow` so it can
+    /// skip a step without ending iteration. `Continue(Some(item))` yields 
+    /// `item`, `Continue(None)` invokes the callback again right away, and 
+    /// `Break(())` ends iteration.
+    ///
+    /// # Arguments
+    /// * `callback`  - The callback that gets invoked by `.next()`.
+    ///                 This callback is passed the original iterator as its
+    ///                 parameter.
+    ///
+    fn iter_map_cf<G, R>(self, callback: G) -> Adapter<(Self::IntoIter, G), R>
+    //
+    where G: FnMut(&mut Self::IntoIter) -> ControlFlow<(), Option<R>>;
+
+    /// Like `.iter_map()`, but only for double-ended iterators, so the 
+    /// callback can take items from the back with `.next_back()`.
+    ///
+    /// # Arguments
+    /// * `callback`  - The callback that gets invoked by `.next()`.
+    ///                 This callback is passed the original iterator as its
+    ///                 parameter.
+    ///
+    fn iter_map_back<F, R>(self, 
+                           callback: F
+                          ) -> ParamFromFnIter<F, Self::IntoIter>
+    //
+    where F: FnMut(&mut Self::IntoIter) -> Option<R>,
+          Self::IntoIter: DoubleEndedIterator;
+
+    /// Like `.iter_map()`, but the original iterator is boxed as a `DynIter`
+    /// so the callback can replace it with a different source, for instance
+    /// to chain to a follow-up source once the current one runs out.
+    ///
+    /// # Arguments
+    /// * `callback`  - The callback that gets invoked by `.next()`.
+    ///                 This callback is passed the boxed inner iterator as 
+    ///                 its parameter.
+    ///
+    #[cfg(feature = "alloc")]
+    fn iter_map_dyn<'a, G, R>(self, 
+                              callback: G
+                             ) -> Adapter<(DynIter<'a, Self::Item>, G), R>
+    //
+    where G: FnMut(&mut DynIter<'a, Self::Item>) -> Option<R>,
+          Self::IntoIter: 'a;
+}
+
+/// Adds `.iter_map()` method to all IntoIterator classes.
+///
+impl<F, I, J, R, T> IntoIterMap<F, I, R, T> for J
+//
+where F: FnMut(&mut I) -> Option<R>,
+      I: Iterator<Item = T>,
+      J: IntoIterator<Item = T, IntoIter = I>,
+{
+    /// Returns an iterator that invokes the callback in `.next()`, passing it
+    /// the original iterator as an argument. The callback can return any
+    /// arbitrary type within an `Option`.
+    ///
+    /// ```
+    /// use iter_map::IntoIterMap;
+    ///
+    /// let mut b = true;
+    ///
+    /// let s = "hello world!".chars().peekable().iter_map(|iter| {
+    ///     if let Some(&ch) = iter.peek() {
+    ///         if ch == 'o' && b {
+    ///             b = false;
+    ///             Some('0')
+    ///         } else {
+    ///             b = true;
+    ///             iter.next()
+    ///         }
+    ///     } else { None }}).collect::<String>();
+    ///
+    /// assert_eq!(&s, "hell0o w0orld!");
+    /// ```
+    fn iter_map(self, callback: F) -> ParamFromFnIter<F, I>
+    {
+        ParamFromFnIter::new(self.into_iter(), callback)
+    }
+}
+
+/// Adds the callback variants of `.iter_map()` to all IntoIterator classes.
+///
+impl<J> IterMapVariants for J
+//
+where J: IntoIterator,
+{
+    /// Returns an iterator that passes the callback the count of items 
+    /// produced so far along with the original iterator. The counter is kept
+    /// in the iterator's data, so no separate counter needs to be captured.
+    ///
+    /// ```
+    /// use iter_map::IterMapVariants;
+    ///
+    /// let v = [1, 2, 3, 4, 5, 6].iter().iter_map_indexed(|i, iter| {
+    ///     if i % 3 == 2 { Some(0) } else { iter.next().copied() }
+    /// }).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 2, 0, 3, 4, 0, 5, 6, 0]);
+    /// ```
+    fn iter_map_indexed<G, R>(self, 
+                              callback: G
+                             ) -> Adapter<(Self::IntoIter, usize, G), R>
+    //
+    where G: FnMut(usize, &mut Self::IntoIter) -> Option<R>,
+    {
+        ParamFromFnIter::new(
+            (self.into_iter(), 0, callback),
+            |(iter, index, callback)| {
+                let item = callback(*index, iter);
+                if item.is_some() {
+                    *index += 1;
+                }
+                item
+            })
+    }
+
+    /// Returns an iterator that yields the callback's `Result`s up to and 
+    /// including the first `Err`. This makes it usable with 
+    /// `.collect::<Result<Vec<_>, _>>()`.
+    ///
+    /// ```
+    /// use iter_map::IterMapVariants;
+    ///
+    /// let r = ["1", "2", "x", "4"].iter().try_iter_map(|iter| {
+    ///     iter.next().map(|s| s.parse::<i32>())
+    /// }).collect::<Result<Vec<_>, _>>();
+    ///
+    /// assert!(r.is_err());
+    /// ```
+    fn try_iter_map<G, R, E>(self, 
+                             callback: G
+                            ) -> Adapter<(Self::IntoIter, bool, G), 
+                                         Result<R, E>>
+    //
+    where G: FnMut(&mut Self::IntoIter) -> Option<Result<R, E>>,
+    {
+        ParamFromFnIter::new(
+            (self.into_iter(), false, callback),
+            |(iter, failed, callback)| {
+                if *failed {
+                    return None;
+                }
+                let item = callback(iter);
+                *failed = matches!(item, Some(Err(_)));
+                item
+            })
+    }
+
+    /// Returns an iterator that drains the items produced by each callback
+    /// invocation, buffering them in the iterator's data.
+    ///
+    /// ```
+    /// use iter_map::IterMapVariants;
+    ///
+    /// let s = "a\tb".chars().iter_flat_map(|iter| {
+    ///     iter.next().map(|ch| {
+    ///         if ch == '\t' { vec![' '; 4] } else { vec![ch] }
+    ///     })
+    /// }).collect::<String>();
+    ///
+    /// assert_eq!(&s, "a    b");
+    /// ```
+    fn iter_flat_map<G, K>(self, 
+                           callback: G
+                          ) -> Adapter<(Self::IntoIter, 
+                                        Option<K::IntoIter>, 
+                                        G), 
+                                       K::Item>
+    //
+    where G: FnMut(&mut Self::IntoIter) -> Option<K>,
+          K: IntoIterator,
+    {
+        ParamFromFnIter::new(
+            (self.into_iter(), None, callback),
+            |(iter, buffer, callback)| {
+                loop {
+                    let item = buffer.as_mut().and_then(Iterator::next);
+                    if item.is_some() {
+                        return item;
+                    }
+                    *buffer = Some(callback(iter)?.into_iter());
+                }
+            })
+    }
+
+    /// Returns an iterator that passes the callback a `Peekable` wrapping the
+    /// original iterator, saving a call to `.peekable()`.
+    ///
+    /// ```
+    /// use iter_map::IterMapVariants;
+    ///
+    /// let mut b = true;
+    ///
+    /// let s = "hello world!".chars().iter_map_peekable(|iter| {
+    ///     if let Some(&ch) = iter.peek() {
+    ///         if ch == 'o' && b {
+    ///             b = false;
+    ///             Some('0')
+    ///         } else {
+    ///             b = true;
+    ///             iter.next()
+    ///         }
+    ///     } else { None }}).collect::<String>();
+    ///
+    /// assert_eq!(&s, "hell0o w0orld!");
+    /// ```
+    fn iter_map_peekable<G, R>(self, 
+                               callback: G
+                              ) -> ParamFromFnIter<G, Peekable<Self::IntoIter>>
+    //
+    where G: FnMut(&mut Peekable<Self::IntoIter>) -> Option<R>,
+    {
+        ParamFromFnIter::new(self.into_iter().peekable(), callback)
+    }
+
+    /// Returns an iterator that passes the callback the next `n` items 
+    /// without consuming them, along with an iterator that does.
+    ///
+    /// ```
+    /// use iter_map::IterMapVariants;
+    ///
+    /// let v = "abc".chars().iter_map_lookahead(2, |ahead, iter| {
+    ///     iter.next()?;
+    ///     Some(ahead.iter().collect::<String>())
+    /// }).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec!["ab", "bc", "c"]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn iter_map_lookahead<G, R>(self, 
+                                n        : usize, 
+                                callback : G
+                               ) -> Adapter<(Lookahead<Self::IntoIter>, 
+                                             Vec<Self::Item>, 
+                                             usize, 
+                                             G), 
+                                            R>
+    //
+    where G: FnMut(&[Self::Item], &mut Lookahead<Self::IntoIter>) -> Option<R>,
+          Self::Item: Clone,
+    {
+        let lookahead = Lookahead { buffer : VecDeque::with_capacity(n), 
+                                    iter   : self.into_iter() };
+        ParamFromFnIter::new(
+            (lookahead, Vec::with_capacity(n), n, callback),
+            |(lookahead, ahead, n, callback)| {
+                lookahead.fill(*n);
+                ahead.clear();
+                ahead.extend(lookahead.buffer.iter().take(*n).cloned());
+                callback(ahead, lookahead)
+            })
+    }
+
+    /// Returns an iterator that invokes the callback until it yields an item 
+    /// or breaks.
+    ///
+    /// ```
+    /// use core::ops::ControlFlow;
+    /// use iter_map::IterMapVariants;
+    ///
+    /// let v = (1..).iter_map_cf(|iter| {
+    ///     match iter.next() {
+    ///         Some(n) if n > 6 => ControlFlow::Break(()),
+    ///         Some(n) if n % 2 == 0 => ControlFlow::Continue(None),
+    ///         n => ControlFlow::Continue(n),
+    ///     }
+    /// }).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 3, 5]);
+    /// ```
+    fn iter_map_cf<G, R>(self, callback: G) -> Adapter<(Self::IntoIter, G), R>
+    //
+    where G: FnMut(&mut Self::IntoIter) -> ControlFlow<(), Option<R>>,
+    {
+        ParamFromFnIter::new(
+            (self.into_iter(), callback),
+            |(iter, callback)| {
+                loop {
+                    match callback(iter) {
+                        ControlFlow::Continue(Some(item)) => return Some(item),
+                        ControlFlow::Continue(None) => continue,
+                        ControlFlow::Break(()) => return None,
+                    }
+                }
+            })
+    }
+
+    /// Returns an iterator that invokes the callback in `.next()`, passing it
+    /// the original iterator, which the callback can consume from either 
+    /// end. For a plain reversal, `ParamFromFnIter::rev_passthrough()` also
+    /// keeps the pass-through fast paths.
+    ///
+    /// ```
+    /// use iter_map::IterMapVariants;
+    ///
+    /// // Alternate between the largest and smallest remaining items.
+    /// let mut back = false;
+    /// let v = [1, 2, 3, 4, 5].iter().iter_map_back(|iter| {
+    ///     back = !back;
+    ///     if back { iter.next_back() } else { iter.next() }
+    /// }).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![&5, &1, &4, &2, &3]);
+    /// ```
+    ///
+    fn iter_map_back<F, R>(self, 
+                           callback: F
+                          ) -> ParamFromFnIter<F, Self::IntoIter>
+    //
+    where F: FnMut(&mut Self::IntoIter) -> Option<R>,
+          Self::IntoIter: DoubleEndedIterator,
+    {
+        ParamFromFnIter::new(self.into_iter(), callback)
+    }
+
+    /// Returns an iterator that invokes the callback in `.next()`, passing it
+    /// the boxed original iterator, which the callback can swap out.
+    ///
+    /// ```
+    /// use iter_map::IterMapVariants;
+    ///
+    /// // Switch to a second range once the first is used up.
+    /// let mut next_source = Some(10..12);
+    /// let v = (1..3).iter_map_dyn(|iter| {
+    ///     loop {
+    ///         if let Some(n) = iter.next() {
+    ///             return Some(n);
+    ///         }
+    ///         *iter = Box::new(next_source.take()?);
+    ///     }
+    /// }).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(v, vec![1, 2, 10, 11]);
+    /// ```
+    ///
+    #[cfg(feature = "alloc")]
+    fn iter_map_dyn<'a, G, R>(self, 
+                              callback: G
+                             ) -> Adapter<(DynIter<'a, Self::Item>, G), R>
+    //
+    where G: FnMut(&mut DynIter<'a, Self::Item>) -> Option<R>,
+          Self::IntoIter: 'a,
+    {
+        ParamFromFnIter::new(
+            (Box::new(self.into_iter()), callback),
+            |(iter, callback)| callback(iter))
+    }
+}
+
+/// Builds a `ParamFromFnIter` over `src` from an inline callback body. The 
+/// identifier before `=>` names the inner iterator within the body.
+///
+/// ```
+/// use iter_map::iter_map;
+///
+/// let v = iter_map!([1, 2, 3, 4].iter(), it => {
+///     it.next().map(|n| n * 10)
+/// }).collect::<Vec<_>>();
+///
+/// assert_eq!(v, vec![10, 20, 30, 40]);
+/// ```
+///
+/// A leading `data = expr,` clause seeds extra state that's stored alongside
+/// the inner iterator. The body then binds both the iterator and the state.
+///
+/// ```
+/// use iter_map::iter_map;
+///
+/// let v = iter_map!(data = 0, 1..=6, it, count => {
+///     *count += 1;
+///     if *count % 3 == 0 { Some(0) } else { it.next() }
+/// }).collect::<Vec<_>>();
+///
+/// assert_eq!(v, vec![1, 2, 0, 3, 4, 0, 5, 6, 0]);
+/// ```
+///
+#[macro_export]
+macro_rules! iter_map {
+    (data = $data:expr, $src:expr, $iter:ident, $state:ident => $body:expr) => {
+        $crate::ParamFromFnIter::new(
+            (::core::iter::IntoIterator::into_iter($src), $data),
+            |($iter, $state)| $body)
+    };
+    ($src:expr, $iter:ident => $body:expr) => {
+        $crate::ParamFromFnIter::new(
+            ::core::iter::IntoIterator::into_iter($src),
+            |$iter| $body)
+    };
+}
 
 #[cfg(test)]
 mod tests {
@@ -196,7 +1860,7 @@ mod tests {
     #[test]
     fn sanity_check() {
         let mut i = 0;
-        let v = [1, 2, 3, 4, 5, 6].iter_map(move |iter| {
+        let v = [1, 2, 3, 4, 5, 6].iter().iter_map(move |iter| {
             i += 1;
             if i % 3 == 0 {
                 Some(0)
@@ -206,4 +1870,655 @@ mod tests {
         }).collect::<Vec<_>>();
         assert_eq!(v, vec![1, 2, 0, 3, 4, 0, 5, 6, 0]);
     }
+
+    #[test]
+    fn default_size_hint() {
+        let iter = [1, 2, 3].iter().iter_map(|iter| iter.next());
+        assert_eq!(iter.size_hint(), (0, None));
+    }
+
+    #[test]
+    fn with_size_hint() {
+        let mut iter = ParamFromFnIter::with_size_hint(
+            [1, 2, 3, 4, 5, 6].iter(), 
+            |iter| iter.next().copied(), 
+            (6, Some(6)));
+        assert_eq!(iter.size_hint(), (6, Some(6)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (5, Some(5)));
+
+        let v = iter.collect::<Vec<_>>();
+        assert_eq!(v.len(), 5);
+        assert!(v.capacity() >= 5);
+    }
+
+    #[test]
+    fn double_ended_interleaved() {
+        use std::collections::VecDeque;
+
+        let data = (1..=6).collect::<VecDeque<_>>();
+        let mut iter = ParamFromFnIterDE::new(data, 
+                                              |d| d.pop_front(), 
+                                              |d| d.pop_back());
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(6));
+        assert_eq!(iter.next_back(), Some(5));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn double_ended_rev() {
+        use std::collections::VecDeque;
+
+        let data = (1..=4).collect::<VecDeque<_>>();
+        let v = ParamFromFnIterDE::new(data, |d| d.pop_front(), 
+                                             |d| d.pop_back())
+                    .rev().collect::<Vec<_>>();
+        assert_eq!(v, vec![4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn fuse_callback() {
+        let mut returned_none = false;
+        let mut iter = [1, 2, 3].iter().iter_map(move |iter| {
+            if returned_none {
+                panic!("callback invoked after returning None");
+            }
+            let item = iter.next().copied();
+            returned_none = item.is_none();
+            item
+        }).fuse_callback();
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn clone_mid_iteration() {
+        let mut i = 0;
+        let mut iter = [1, 2, 3, 4, 5, 6].iter().iter_map(move |iter| {
+            i += 1;
+            if i % 3 == 0 {
+                Some(0)
+            } else {
+                iter.next().copied()
+            }
+        });
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+
+        let clone = iter.clone();
+        assert_eq!(iter.collect::<Vec<_>>(), vec![0, 3, 4, 0, 5, 6, 0]);
+        assert_eq!(clone.collect::<Vec<_>>(), vec![0, 3, 4, 0, 5, 6, 0]);
+    }
+
+    #[test]
+    fn debug_shows_data() {
+        let iter = ParamFromFnIter::new(vec![1, 2, 3], |v| v.pop());
+        let s = format!("{:?}", iter);
+        assert!(s.contains("[1, 2, 3]"));
+        assert!(s.contains("callback: <closure>"));
+    }
+
+    #[test]
+    fn into_data_resumes_inner() {
+        let mut iter = [1, 2, 3, 4, 5].iter().peekable()
+                                      .iter_map(|iter| iter.next());
+        assert_eq!(iter.next(), Some(&1));
+        assert_eq!(iter.next(), Some(&2));
+
+        let mut inner = iter.into_data();
+        assert_eq!(inner.peek(), Some(&&3));
+        assert_eq!(inner.copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn into_parts_returns_callback() {
+        let mut iter = (1..=4).iter_map(|iter| iter.next().map(|n| n * 10));
+        assert_eq!(iter.next(), Some(10));
+
+        let (callback, data) = iter.into_parts();
+        let v = ParamFromFnIter::new(data, callback).collect::<Vec<_>>();
+        assert_eq!(v, vec![20, 30, 40]);
+    }
+
+    #[test]
+    fn data_accessors() {
+        let mut iter = [1, 2, 3, 4].iter().peekable()
+                                   .iter_map(|iter| iter.next().copied());
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.data_mut().peek(), Some(&&2));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.data().size_hint(), (2, Some(2)));
+    }
+
+    #[test]
+    fn data_mut_between_calls() {
+        let mut iter = ParamFromFnIter::new(0, |count: &mut i32| {
+            *count += 1;
+            Some(*count)
+        });
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        *iter.data_mut() = 0;
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(*iter.data(), 1);
+    }
+
+    #[test]
+    fn iter_map_indexed_counts_produced() {
+        let mut seen = vec![];
+        let v = (1..=6).iter_map_indexed(|i, iter| {
+            seen.push(i);
+            // Skip the odd inner items.
+            iter.next();
+            iter.next()
+        }).collect::<Vec<_>>();
+        assert_eq!(v, vec![2, 4, 6]);
+        assert_eq!(seen, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn iter_map_indexed_skipping_callback() {
+        let v = (0..10).iter_map_indexed(|i, iter| {
+            iter.find(|n| n % 3 == 0).map(|n| (i, n))
+        }).collect::<Vec<_>>();
+        assert_eq!(v, vec![(0, 0), (1, 3), (2, 6), (3, 9)]);
+    }
+
+    #[test]
+    fn try_iter_map_happy_path() {
+        let r = ["1", "2", "3"].iter().try_iter_map(|iter| {
+            iter.next().map(|s| s.parse::<i32>())
+        }).collect::<Result<Vec<_>, _>>();
+        assert_eq!(r, Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn try_iter_map_early_error() {
+        let v = ["1", "x", "3"].iter().try_iter_map(|iter| {
+            iter.next().map(|s| s.parse::<i32>())
+        }).collect::<Vec<_>>();
+        assert_eq!(v.len(), 2);
+        assert_eq!(v[0], Ok(1));
+        assert!(v[1].is_err());
+    }
+
+    #[test]
+    fn try_iter_map_stops_calling_after_error() {
+        let calls = std::cell::Cell::new(0);
+        let mut iter = (0..5).try_iter_map(|iter| {
+            calls.set(calls.get() + 1);
+            iter.next().map(|n| if n == 1 { Err(n) } else { Ok(n) })
+        });
+        assert_eq!(iter.next(), Some(Ok(0)));
+        assert_eq!(iter.next(), Some(Err(1)));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn iter_flat_map_expansion() {
+        // Each inner item n expands to n copies of itself.
+        let v = [0, 1, 3, 0, 2].iter().iter_flat_map(|iter| {
+            iter.next().map(|&n| vec![n; n])
+        }).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 3, 3, 3, 2, 2]);
+    }
+
+    #[test]
+    fn iter_flat_map_all_empty() {
+        let v = (0..4).iter_flat_map(|iter| {
+            iter.next().map(|_| Vec::<i32>::new())
+        }).collect::<Vec<_>>();
+        assert!(v.is_empty());
+    }
+
+    #[test]
+    fn iter_map_peekable_readme_example() {
+        let mut b = true;
+        let s = "hello world!".chars().iter_map_peekable(|iter| {
+            let &ch = iter.peek()?;
+            if ch == 'o' && b {
+                b = false;
+                Some('0')
+            } else {
+                b = true;
+                iter.next()
+            }
+        }).collect::<String>();
+        assert_eq!(&s, "hell0o w0orld!");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn iter_map_lookahead_bigrams() {
+        let v = [1, 2, 3, 4].iter().iter_map_lookahead(2, |ahead, iter| {
+            if ahead.len() < 2 {
+                return None;
+            }
+            iter.next();
+            Some((*ahead[0], *ahead[1]))
+        }).collect::<Vec<_>>();
+        assert_eq!(v, vec![(1, 2), (2, 3), (3, 4)]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn iter_map_lookahead_tail() {
+        let mut lens = vec![];
+        let v = (1..=3).iter_map_lookahead(2, |ahead, iter| {
+            lens.push(ahead.len());
+            iter.next()
+        }).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3]);
+        assert_eq!(lens, vec![2, 2, 1, 0]);
+    }
+
+    #[test]
+    fn count_drives_callback() {
+        let mut i = 0;
+        let iter = [1, 2, 3, 4, 5, 6].iter().iter_map(move |iter| {
+            i += 1;
+            if i % 3 == 0 { Some(&0) } else { iter.next() }
+        });
+        assert_eq!(iter.count(), 9);
+    }
+
+    #[test]
+    fn count_via_data_matches_pass_through() {
+        let iter = (0..100).iter_map(|iter| iter.next());
+        assert_eq!(iter.clone().count(), iter.count_via_data());
+    }
+
+    #[test]
+    fn passthrough_nth_matches_default() {
+        let mut fast = ParamFromFnIter::new_passthrough(0..100);
+        let mut slow = (0..100).iter_map(|iter| iter.next());
+        for n in [0, 1, 5, 30, 80] {
+            assert_eq!(fast.nth(n), slow.nth(n));
+        }
+        assert_eq!(fast.next(), slow.next());
+    }
+
+    #[test]
+    fn passthrough_last_matches_default() {
+        let fast = ParamFromFnIter::new_passthrough([1, 2, 3].iter());
+        let slow = [1, 2, 3].iter().iter_map(|iter| iter.next());
+        assert_eq!(fast.last(), slow.last());
+
+        let odd = || (0..10).filter(|n| n % 4 == 1);
+        let fast = ParamFromFnIter::new_passthrough(odd());
+        let slow = odd().iter_map(|iter| iter.next());
+        assert_eq!(fast.last(), slow.last());
+
+        let fast = ParamFromFnIter::new_passthrough(0..0);
+        assert_eq!(fast.last(), None);
+    }
+
+    #[test]
+    fn passthrough_size_hint() {
+        let mut iter = ParamFromFnIter::new_passthrough([1, 2, 3].iter());
+        assert_eq!(iter.size_hint(), (3, Some(3)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+    }
+
+    #[test]
+    fn iter_map_cf_all_arms() {
+        let mut iter = [1, 0, 2, 0, 0, 3, -1, 4].iter().iter_map_cf(|iter| {
+            match iter.next() {
+                Some(&n) if n < 0 => ControlFlow::Break(()),
+                Some(0) => ControlFlow::Continue(None),
+                Some(&n) => ControlFlow::Continue(Some(n)),
+                None => ControlFlow::Break(()),
+            }
+        });
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.into_data().0.next(), Some(&4));
+    }
+
+    #[test]
+    fn iter_map_cf_skips_do_not_end() {
+        let mut calls = 0;
+        let v = (0..10).iter_map_cf(|iter| {
+            calls += 1;
+            match iter.next() {
+                Some(n) if n < 9 => ControlFlow::Continue(None),
+                n => n.map_or(ControlFlow::Break(()), |n| {
+                    ControlFlow::Continue(Some(n))
+                }),
+            }
+        }).collect::<Vec<_>>();
+        assert_eq!(v, vec![9]);
+        assert_eq!(calls, 11);
+    }
+
+    #[test]
+    fn repeat_with_counts_down() {
+        let mut n = 3;
+        let v = repeat_with(|| if n > 0 { n -= 1; Some(n) } else { None })
+                    .collect::<Vec<_>>();
+        assert_eq!(v, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn repeat_with_composes_with_adapters() {
+        let mut n = 0;
+        let v = repeat_with(|| { n += 1; Some(n) }).cap(3).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn replace_data_keeps_callback_state() {
+        let mut count = 0;
+        let mut iter = [1, 2].iter().iter_map(move |iter| {
+            count += 1;
+            iter.next().map(|n| (count, *n))
+        });
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![(1, 1), (2, 2)]);
+        let mut old = iter.replace_data([7, 8].iter());
+        assert_eq!(old.next(), None);
+        assert_eq!(iter.collect::<Vec<_>>(), vec![(4, 7), (5, 8)]);
+    }
+
+    #[test]
+    fn map_data_callback_sees_new_data() {
+        type Inner<'a> = Box<dyn Iterator<Item = i32> + 'a>;
+        let mut seen = 0;
+        let data: Inner = Box::new(1..=3);
+        let iter = ParamFromFnIter::new(data, |it: &mut Inner| {
+            seen += 1;
+            it.next()
+        });
+        let iter = iter.map_data(|it| Box::new(it.map(|n| -n)) as Inner);
+        assert_eq!(iter.collect::<Vec<_>>(), vec![-1, -2, -3]);
+        assert_eq!(seen, 4);
+    }
+
+    #[test]
+    fn map_data_drops_passthrough() {
+        let iter = ParamFromFnIter::new_passthrough(0..10).map_data(|d| d);
+        assert_eq!(iter.size_hint(), (0, None));
+        assert_eq!(iter.count(), 10);
+    }
+
+    #[test]
+    fn mutable_borrow_resumes() {
+        let mut calls = 0;
+        let mut iter = (1..=6).iter_map(|iter| { calls += 1; iter.next() });
+        let first = (&mut iter).take(2).collect::<Vec<_>>();
+        let mut rest = Vec::new();
+        for n in &mut iter {
+            rest.push(n);
+        }
+        assert_eq!(first, vec![1, 2]);
+        assert_eq!(rest, vec![3, 4, 5, 6]);
+        assert_eq!(calls, 7);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn instrument_readme_example() {
+        let counters = Rc::new(Counters::default());
+        let mut b = true;
+        let s = "hello world!".chars().peekable().iter_map(|iter| {
+            if let Some(&ch) = iter.peek() {
+                if ch == 'o' && b {
+                    b = false;
+                    Some('0')
+                } else {
+                    b = true;
+                    iter.next()
+                }
+            } else { None }
+        }).instrument(counters.clone()).collect::<String>();
+        assert_eq!(&s, "hell0o w0orld!");
+        assert_eq!(counters.calls.load(Ordering::Relaxed), 15);
+        assert_eq!(counters.yielded.load(Ordering::Relaxed), 14);
+        assert_eq!(counters.skipped.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn instrument_bypasses_passthrough() {
+        let counters = Rc::new(Counters::default());
+        let mut iter = ParamFromFnIter::new_passthrough(0..10)
+                                       .instrument(counters.clone());
+        assert_eq!(iter.nth(4), Some(4));
+        assert_eq!(counters.calls.load(Ordering::Relaxed), 5);
+        assert_eq!(iter.into_data().next(), Some(5));
+    }
+
+    #[test]
+    fn into_peekable_peek_then_next() {
+        let mut calls = 0;
+        let mut iter = [1, 2].iter().iter_map(|iter| {
+            calls += 1;
+            iter.next().copied()
+        }).into_peekable();
+        assert_eq!(iter.peek(), Some(&1));
+        assert_eq!(iter.peek(), Some(&1));
+        assert_eq!(iter.data().len(), 1);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.peek(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn into_peekable_data_mut_and_size_hint() {
+        let mut iter = ParamFromFnIter::new_passthrough(0..5).into_peekable();
+        assert_eq!(iter.peek(), Some(&0));
+        assert_eq!(iter.size_hint(), (5, Some(5)));
+        *iter.data_mut() = 10..12;
+        assert_eq!(iter.collect::<Vec<_>>(), vec![0, 10, 11]);
+    }
+
+    #[test]
+    fn reset_replays_passthrough_output() {
+        let mut iter = ParamFromFnIter::new_resettable([1, 2, 3].iter(), 
+                                                       |it| it.next());
+        let first = iter.by_ref().collect::<Vec<_>>();
+        iter.reset();
+        let second = iter.by_ref().take(2).collect::<Vec<_>>();
+        iter.reset();
+        assert_eq!(first, vec![&1, &2, &3]);
+        assert_eq!(second, vec![&1, &2]);
+        assert_eq!(iter.collect::<Vec<_>>(), first);
+    }
+
+    #[test]
+    fn reset_keeps_callback_state() {
+        let mut calls = 0;
+        let mut iter = ParamFromFnIter::new_resettable(0..2, |it| {
+            calls += 1;
+            it.next().map(|n| (calls, n))
+        });
+        assert_eq!(iter.by_ref().count(), 2);
+        iter.reset();
+        assert_eq!(iter.next(), Some((4, 0)));
+    }
+
+    #[test]
+    fn into_resettable_keeps_passthrough() {
+        let mut iter = ParamFromFnIter::new_passthrough(0..5).into_resettable();
+        assert_eq!(iter.size_hint(), (5, Some(5)));
+        assert_eq!(iter.nth(1), Some(1));
+        iter.reset();
+        assert_eq!(iter.data().clone().next(), Some(0));
+        assert_eq!(iter.into_inner().last(), Some(4));
+    }
+
+    #[test]
+    fn with_stats_skipping_callback() {
+        let mut n = 0;
+        let v = (1..=9).iter_map(|iter| {
+            n += 1;
+            if n % 3 == 0 {
+                iter.next();
+            }
+            iter.next()
+        }).with_stats().collect::<Vec<_>>();
+        let items = v.iter().map(|(_, item)| *item).collect::<Vec<_>>();
+        assert_eq!(items, vec![1, 2, 4, 5, 6, 8, 9]);
+        for (i, (stats, _)) in v.iter().enumerate() {
+            assert_eq!(*stats, Stats { produced       : i + 1, 
+                                       callback_calls : i + 1 });
+        }
+    }
+
+    #[test]
+    fn with_stats_counts_none_calls() {
+        let mut iter = [1, 2].iter().iter_map(|iter| iter.next()).with_stats();
+        assert_eq!(iter.by_ref().count(), 2);
+        assert_eq!(iter.next(), None);
+        let (_, stats) = iter.into_data();
+        assert_eq!(stats, Stats { produced: 2, callback_calls: 4 });
+    }
+
+    #[test]
+    fn checkpoint_restore_replays_tail() {
+        let mut iter = ParamFromFnIter::new_resettable(0..20, 
+                                                       |it| it.next()
+                                                              .map(|n| n * n));
+        let first = iter.by_ref().take(5).collect::<Vec<_>>();
+        let checkpoint = iter.checkpoint();
+        let second = iter.by_ref().take(5).collect::<Vec<_>>();
+        iter.restore(checkpoint);
+        let again = iter.by_ref().take(5).collect::<Vec<_>>();
+        assert_eq!(first, vec![0, 1, 4, 9, 16]);
+        assert_eq!(second, vec![25, 36, 49, 64, 81]);
+        assert_eq!(again, second);
+        assert_eq!(iter.checkpoint(), Checkpoint(10));
+    }
+
+    #[test]
+    fn checkpoint_counts_from_last_reset() {
+        let mut iter = ParamFromFnIter::new_resettable(0..3, |it| it.next());
+        assert_eq!(iter.by_ref().count(), 3);
+        iter.reset();
+        assert_eq!(iter.checkpoint(), Checkpoint(0));
+        iter.restore(Checkpoint(99));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn iter_map_back_reverses_slice() {
+        let data = [1, 2, 3, 4];
+        let v = data.iter().iter_map_back(|iter| iter.next_back())
+                           .collect::<Vec<_>>();
+        assert_eq!(v, vec![&4, &3, &2, &1]);
+    }
+
+    #[test]
+    fn rev_passthrough_fast_paths() {
+        let mut iter = ParamFromFnIter::rev_passthrough(0..1_000_000);
+        assert_eq!(iter.nth(9), Some(999_990));
+        assert_eq!(iter.size_hint(), (999_990, Some(999_990)));
+        assert_eq!(iter.last(), Some(0));
+        let v = ParamFromFnIter::rev_passthrough("abc".chars())
+                                .collect::<String>();
+        assert_eq!(&v, "cba");
+    }
+
+    #[test]
+    fn from_std_into_std_round_trip() {
+        let mut n = 0;
+        let iter = ParamFromFnIter::from_std(move || {
+            n += 1;
+            if n <= 4 { Some(n) } else { None }
+        });
+        let v = core::iter::from_fn(iter.into_std()).collect::<Vec<_>>();
+        assert_eq!(v, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn into_std_from_std_round_trip() {
+        let mut next = (0..3).iter_map(|iter| iter.next().map(|n| n * 2))
+                             .into_std();
+        assert_eq!(next(), Some(0));
+        let v = ParamFromFnIter::from_std(next).collect::<Vec<_>>();
+        assert_eq!(v, vec![2, 4]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn iter_map_dyn_switches_sources() {
+        let mut sources = vec![20..22, 10..12];
+        let v = (0..2).iter_map_dyn(|iter| {
+            loop {
+                if let Some(n) = iter.next() {
+                    return Some(n);
+                }
+                *iter = Box::new(sources.pop()?);
+            }
+        }).collect::<Vec<_>>();
+        assert_eq!(v, vec![0, 1, 10, 11, 20, 21]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn iter_map_dyn_switches_mid_stream() {
+        let data = [1, 2, 3, 4];
+        let mut iter = data.iter().copied().iter_map_dyn(|iter| {
+            let n = iter.next()?;
+            if n == 2 {
+                *iter = Box::new(core::iter::repeat(0).take(2));
+            }
+            Some(n)
+        });
+        assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![1, 2, 0, 0]);
+        assert_eq!(iter.data_mut().0.next(), None);
+    }
+
+    #[test]
+    fn compose_skip_every_third_after_doubling() {
+        let mut i = 0;
+        let v = (1..=7).iter_map(|iter| iter.next().map(|n| n * 2))
+                       .compose(move |iter| {
+                           loop {
+                               let n = iter.next()?;
+                               i += 1;
+                               if i % 3 != 0 {
+                                   return Some(n);
+                               }
+                           }
+                       })
+                       .collect::<Vec<_>>();
+        assert_eq!(v, vec![2, 4, 8, 10, 14]);
+    }
+
+    #[test]
+    fn compose_reaches_previous_stage() {
+        let mut iter = (1..=3).iter_map(|iter| iter.next())
+                              .compose(|iter| iter.next().map(|n| n * 2));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.data_mut().next(), Some(2));
+        assert_eq!(iter.collect::<Vec<_>>(), vec![6]);
+    }
+
+    #[test]
+    fn passthrough_nth_past_the_end_checkpoint() {
+        let mut iter = ParamFromFnIter::new_passthrough(0..10)
+                                        .into_resettable();
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.nth(usize::MAX), None);
+        assert_eq!(iter.checkpoint(), Checkpoint(10));
+        let mut iter = ParamFromFnIter::rev_passthrough(0..10)
+                                        .into_resettable();
+        assert_eq!(iter.nth(3), Some(6));
+        assert_eq!(iter.nth(20), None);
+        assert_eq!(iter.checkpoint(), Checkpoint(10));
+    }
 }
@@ -0,0 +1,91 @@
+
+//! A bridge from `.iter_map()` to rayon, enabled by the `rayon` feature.
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::IntoIterMap;
+
+/// A trait to add the `.par_iter_map()` method to rayon's parallel sources.
+///
+pub trait ParIterMap: IntoParallelIterator + Sized
+{
+    /// Collects the source, runs `.iter_map()` over it and returns the 
+    /// transformed items as a parallel iterator. 
+    ///
+    /// The map stage itself stays sequential: the callback is an `FnMut` 
+    /// with state of its own and is handed the ordered source iterator, so 
+    /// it can't be fanned out. Only the consumption of its results is 
+    /// parallel, which pays off when downstream work dominates.
+    ///
+    /// ```
+    /// use iter_map::ParIterMap;
+    /// use rayon::prelude::*;
+    ///
+    /// let sum = (0..1000_u64).par_iter_map(|iter| {
+    ///     iter.next().map(|n| n * 2)
+    /// }).sum::<u64>();
+    ///
+    /// assert_eq!(sum, 999_000);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `callback`  - The callback that gets invoked for each transformed 
+    ///                 item. This callback is passed an iterator over the 
+    ///                 collected source items as its parameter.
+    ///
+    fn par_iter_map<F, R>(self, callback: F) -> rayon::vec::IntoIter<R>
+    //
+    where F: FnMut(&mut std::vec::IntoIter<Self::Item>) -> Option<R>,
+          R: Send,
+    {
+        let source = self.into_par_iter().collect::<Vec<_>>();
+        source.iter_map(callback)
+              .collect::<Vec<_>>()
+              .into_par_iter()
+    }
+}
+
+/// Adds `.par_iter_map()` method to all IntoParallelIterator classes.
+///
+impl<P> ParIterMap for P
+//
+where P: IntoParallelIterator,
+{
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    use rayon::prelude::*;
+
+    /// Doubles each item and inserts a count after every two.
+    fn transform<I>(i: &mut u64, iter: &mut I) -> Option<u64>
+    where I: Iterator<Item = u64>,
+    {
+        *i += 1;
+        if *i % 3 == 0 { Some(*i) } else { iter.next().map(|n| n * 2) }
+    }
+
+    #[test]
+    fn par_sum_matches_sequential() {
+        let mut i = 0;
+        let sequential = (0..100_000_u64).iter_map(|it| transform(&mut i, it))
+                                         .sum::<u64>();
+        let mut i = 0;
+        let parallel = (0..100_000_u64).par_iter_map(|it| transform(&mut i, it))
+                                       .sum::<u64>();
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn par_preserves_order() {
+        let v = (0..1000).into_par_iter()
+                         .filter(|n| n % 7 == 0)
+                         .par_iter_map(|it| it.next())
+                         .collect::<Vec<_>>();
+        let expected = (0..1000).filter(|n| n % 7 == 0).collect::<Vec<_>>();
+        assert_eq!(v, expected);
+    }
+}
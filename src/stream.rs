@@ -0,0 +1,175 @@
+
+//! An async counterpart of `ParamFromFnIter`, enabled by the `stream` 
+//! feature.
+
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use futures_core::Stream;
+
+/// With ParamFromFnStream you can create streams simply by calling 
+/// `ParamFromFnStream::new()` and passing it a callback. The callback will be
+/// invoked each time the stream is polled and is passed the data and the 
+/// task context, so it can poll an inner stream stored in the data.
+///
+/// The callback and data must be `Unpin` for the stream to be polled. Inner
+/// streams that aren't can be stored as `Pin<Box<_>>`.
+///
+pub struct ParamFromFnStream<F, D>
+{
+    callback: F,
+    data: D,
+}
+
+impl<F, D, R> ParamFromFnStream<F, D>
+//
+where F: FnMut(&mut D, &mut Context<'_>) -> Poll<Option<R>>,
+{
+    /// Creates a new `ParamFromFnStream` instance.
+    ///
+    /// ```
+    /// use futures::executor::block_on;
+    /// use futures::stream::{self, StreamExt};
+    /// use iter_map::ParamFromFnStream;
+    ///
+    /// let s = ParamFromFnStream::new(stream::iter(1..=3), |inner, cx| {
+    ///     inner.poll_next_unpin(cx).map(|item| item.map(|n| n * 2))
+    /// });
+    ///
+    /// assert_eq!(block_on(s.collect::<Vec<_>>()), vec![2, 4, 6]);
+    /// ```
+    ///
+    /// # Arguments
+    /// * `data`      - Data that will be passed to the callback on each 
+    ///                 invocation.
+    /// * `callback`  - The callback that gets invoked when the stream is 
+    ///                 polled.
+    ///    
+    pub fn new(data: D, callback: F) -> Self
+    {
+        ParamFromFnStream { callback, data }
+    }
+}
+
+impl<F, D> ParamFromFnStream<F, D>
+{
+    /// Returns a reference to the data passed to the callback.
+    ///
+    pub fn data(&self) -> &D
+    {
+        &self.data
+    }
+
+    /// Consumes the stream and returns its data.
+    ///
+    pub fn into_data(self) -> D
+    {
+        self.data
+    }
+}
+
+/// Implements Stream for ParamFromFnStream.
+///
+impl<F, D, R> Stream for ParamFromFnStream<F, D>
+//
+where F: FnMut(&mut D, &mut Context<'_>) -> Poll<Option<R>> + Unpin,
+      D: Unpin,
+{
+    type Item = R;
+
+    /// Invokes the callback, passing it `&mut self.data` and the context.
+    ///
+    fn poll_next(self: Pin<&mut Self>, 
+                 cx: &mut Context<'_>
+                ) -> Poll<Option<Self::Item>>
+    {
+        let this = self.get_mut();
+        (this.callback)(&mut this.data, cx)
+    }
+}
+
+/// A trait to add the `.stream_map()` method to any existing stream.
+///
+pub trait StreamMapExt: Stream + Sized
+{
+    /// Returns a `ParamFromFnStream` which wraps the stream it's invoked on. 
+    /// This is the async analog of `.iter_map()`.
+    ///
+    /// ```
+    /// use futures::executor::block_on;
+    /// use futures::stream::{self, StreamExt};
+    /// use iter_map::StreamMapExt;
+    ///
+    /// let s = stream::iter("ab".chars()).stream_map(|inner, cx| {
+    ///     inner.poll_next_unpin(cx)
+    ///          .map(|item| item.map(|ch| ch.to_ascii_uppercase()))
+    /// });
+    ///
+    /// assert_eq!(block_on(s.collect::<String>()), "AB");
+    /// ```
+    ///
+    /// # Arguments
+    /// * `callback`  - The callback that gets invoked when the stream is 
+    ///                 polled. This callback is passed the original stream and
+    ///                 the task context as its parameters.
+    ///
+    fn stream_map<F, R>(self, callback: F) -> ParamFromFnStream<F, Self>
+    //
+    where F: FnMut(&mut Self, &mut Context<'_>) -> Poll<Option<R>>,
+    {
+        ParamFromFnStream::new(self, callback)
+    }
+}
+
+/// Adds `.stream_map()` method to all Stream classes.
+///
+impl<S> StreamMapExt for S
+//
+where S: Stream,
+{
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    use core::task::Poll;
+    use futures::executor::block_on;
+    use futures::stream::{self, StreamExt};
+
+    #[test]
+    fn stream_to_completion() {
+        let s = ParamFromFnStream::new(stream::iter(1..=6), |inner, cx| {
+            // Skip the odd items.
+            loop {
+                match inner.poll_next_unpin(cx) {
+                    Poll::Ready(Some(n)) if n % 2 == 1 => continue,
+                    poll => return poll,
+                }
+            }
+        });
+        assert_eq!(block_on(s.collect::<Vec<_>>()), vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn stream_map_to_completion() {
+        let mut i = 0;
+        let mut s = stream::iter(1..=4).stream_map(move |inner, cx| {
+            i += 1;
+            if i % 3 == 0 {
+                Poll::Ready(Some(0))
+            } else {
+                inner.poll_next_unpin(cx)
+            }
+        });
+        block_on(async {
+            let mut v = vec![];
+            while let Some(n) = s.next().await {
+                v.push(n);
+            }
+            assert_eq!(v, vec![1, 2, 0, 3, 4, 0]);
+        });
+        assert_eq!(block_on(s.into_data().next()), None);
+    }
+}
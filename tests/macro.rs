@@ -0,0 +1,40 @@
+//! Tests for the `iter_map!` macro.
+
+use iter_map::iter_map;
+
+#[test]
+fn without_data() {
+    let mut i = 0;
+    let v = iter_map!([1, 2, 3, 4, 5, 6].iter(), iter => {
+        i += 1;
+        if i % 3 == 0 { Some(0) } else { iter.next().copied() }
+    }).collect::<Vec<_>>();
+    assert_eq!(v, vec![1, 2, 0, 3, 4, 0, 5, 6, 0]);
+}
+
+#[test]
+fn with_data() {
+    let mut iter = iter_map!(data = 0, "abc".chars(), chars, seen => {
+        let ch = chars.next()?;
+        *seen += 1;
+        Some(ch.to_ascii_uppercase())
+    });
+    assert_eq!(iter.by_ref().collect::<String>(), "ABC");
+    assert_eq!(iter.data().1, 3);
+}
+
+#[test]
+fn hygiene() {
+    // Names the expansion might use internally don't clash with the user's.
+    let data = vec![1, 2];
+    let callback = 10;
+    let v = iter_map!(data, src => src.next().map(|n| n + callback))
+                .collect::<Vec<_>>();
+    assert_eq!(v, vec![11, 12]);
+}
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,34 @@
+//! Exercises the crate from a `no_std` crate using only `core` iterators.
+//! Run with `cargo test --no-default-features` to also build the library 
+//! itself without `std`.
+
+#![no_std]
+
+use iter_map::{IntoIterMap, ParamFromFnIter};
+
+#[test]
+fn iter_map_on_slice_iter() {
+    let mut i = 0;
+    let mut out = [0; 9];
+    let iter = [1, 2, 3, 4, 5, 6].iter().iter_map(move |iter| {
+        i += 1;
+        if i % 3 == 0 {
+            Some(0)
+        } else {
+            iter.next().copied()
+        }
+    });
+    for (slot, n) in out.iter_mut().zip(iter) {
+        *slot = n;
+    }
+    assert_eq!(out, [1, 2, 0, 3, 4, 0, 5, 6, 0]);
+}
+
+#[test]
+fn param_from_fn_iter_new() {
+    let iter = ParamFromFnIter::new(0, |n: &mut u32| {
+        *n += 1;
+        if *n <= 3 { Some(*n) } else { None }
+    });
+    assert_eq!(iter.sum::<u32>(), 6);
+}
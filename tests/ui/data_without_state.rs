@@ -0,0 +1,5 @@
+use iter_map::iter_map;
+
+fn main() {
+    let _ = iter_map!(data = 0, 1..3, it => it.next());
+}
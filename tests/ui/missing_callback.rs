@@ -0,0 +1,5 @@
+use iter_map::iter_map;
+
+fn main() {
+    let _ = iter_map!(1..3);
+}